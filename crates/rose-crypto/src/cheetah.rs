@@ -1,5 +1,6 @@
 use ibig::UBig;
 use rose_ztd::{
+    belts_to_ubig,
     crypto::cheetah::{
         ch_add, ch_neg, ch_scal_big, trunc_g_order, CheetahPoint, F6lt, A_GEN, G_ORDER,
     },
@@ -13,6 +14,17 @@ extern crate alloc;
 pub struct PublicKey(pub CheetahPoint);
 
 impl PublicKey {
+    /// Public key of `private_key`, i.e. the private scalar times the curve generator.
+    ///
+    /// This determines pubkey hashes, and thus the names of notes locked to them.
+    pub fn from_private(private_key: &PrivateKey) -> PublicKey {
+        PublicKey(ch_scal_big(&private_key.0, &A_GEN).unwrap())
+    }
+
+    /// Verify a Schnorr signature of the challenge digest `m`, as made by [`PrivateKey::sign`].
+    ///
+    /// Checks that `c` and `s` are canonical nonzero scalars, and that `c` equals the truncated
+    /// hash of `(s*G - c*P, P, m)`, where `P` is this key.
     pub fn verify(&self, m: &Digest, sig: &Signature) -> bool {
         if sig.c == UBig::from(0u64)
             || sig.c >= *G_ORDER
@@ -157,12 +169,95 @@ impl Hashable for PublicKey {
     }
 }
 
+impl PublicKey {
+    /// Identity key of this public key, suitable for `HashSet`/`HashMap` lookups.
+    ///
+    /// Two public keys are considered the same identity if their hashes match.
+    pub fn as_key(&self) -> PubkeyHash {
+        PubkeyHash(self.hash())
+    }
+
+    /// Canonical ordering of public keys, by their [`PublicKey::to_be_bytes`] encoding.
+    ///
+    /// Unlike the derived `Ord`, this doesn't depend on the in-memory layout, so other
+    /// implementations can reproduce it.
+    pub fn cmp_canonical(&self, other: &PublicKey) -> core::cmp::Ordering {
+        self.to_be_bytes().cmp(&other.to_be_bytes())
+    }
+
+    /// Combined public key of `keys` for multiparty signing (see [`PrivateKey::sign_multi`]).
+    ///
+    /// This is the naive sum of the keys, with no MuSig key-aggregation coefficients, so it is
+    /// not safe against rogue-key attacks: a co-signer who picks their key after seeing the
+    /// others' can control the aggregate. Only use it with keys that are known to be
+    /// independently generated, e.g. with a proof of possession.
+    pub fn aggregate<'a>(keys: impl IntoIterator<Item = &'a PublicKey>) -> PublicKey {
+        keys.into_iter().sum()
+    }
+}
+
+/// Public key hash, used as the canonical identity of a [`PublicKey`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PubkeyHash(pub Digest);
+
+impl core::hash::Hash for PubkeyHash {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        for belt in &self.0 .0 {
+            state.write_u64(belt.0);
+        }
+    }
+}
+
+impl From<&PublicKey> for PubkeyHash {
+    fn from(pubkey: &PublicKey) -> Self {
+        pubkey.as_key()
+    }
+}
+
+impl From<PubkeyHash> for Digest {
+    fn from(key: PubkeyHash) -> Self {
+        key.0
+    }
+}
+
+/// Verify many signatures, e.g. the pkh signatures of a multisig spend. Returns `false` if any of
+/// them is invalid.
+///
+/// Signatures carry the challenge `c` rather than the nonce point `R = s*G - c*P`, which is only
+/// checked through the hash giving `c`. The verification equations thus cannot be merged into a
+/// random linear combination, as every nonce point has to be recomputed and hashed. Signatures are
+/// verified in turn, stopping at the first invalid one.
+pub fn batch_verify(items: &[(PublicKey, Digest, Signature)]) -> bool {
+    items.iter().all(|(key, m, sig)| key.verify(m, sig))
+}
+
 #[derive(Debug, Clone)]
 pub struct Signature {
     pub c: UBig, // challenge
     pub s: UBig, // signature scalar
 }
 
+impl Signature {
+    /// Whether both components are reduced scalars (`0 < c, s < G_ORDER`).
+    ///
+    /// This is the only form [`PublicKey::verify`] accepts, and the only one signing produces.
+    /// Unlike ECDSA there is no low-`s` rule: the challenge commits to the nonce point, so
+    /// `(c, G_ORDER - s)` is not a valid signature for the same message.
+    pub fn is_canonical(&self) -> bool {
+        let zero = UBig::from(0u64);
+        self.c != zero && self.c < *G_ORDER && self.s != zero && self.s < *G_ORDER
+    }
+
+    /// Reduce both components modulo the group order.
+    ///
+    /// Signatures decoded from untrusted sources may carry unreduced components that are
+    /// congruent to a valid signature but encode (and hash) differently.
+    pub fn normalize(&mut self) {
+        self.c %= &*G_ORDER;
+        self.s %= &*G_ORDER;
+    }
+}
+
 // Aggregate signature of the same challenge
 impl core::iter::Sum<Signature> for Option<Signature> {
     fn sum<I: Iterator<Item = Signature>>(mut iter: I) -> Self {
@@ -192,12 +287,9 @@ impl NounDecode for Signature {
     fn from_noun(noun: &Noun) -> Option<Self> {
         let (c, s): ([Belt; 8], [Belt; 8]) = NounDecode::from_noun(noun)?;
 
-        let c = Belt::to_bytes(&c);
-        let s = Belt::to_bytes(&s);
-
         Some(Signature {
-            c: UBig::from_le_bytes(&c),
-            s: UBig::from_le_bytes(&s),
+            c: belts_to_ubig(&c),
+            s: belts_to_ubig(&s),
         })
     }
 }
@@ -213,7 +305,7 @@ pub struct PrivateKey(pub UBig);
 
 impl PrivateKey {
     pub fn public_key(&self) -> PublicKey {
-        PublicKey(ch_scal_big(&self.0, &A_GEN).unwrap())
+        PublicKey::from_private(self)
     }
 
     pub fn sign(&self, m: &Digest) -> Signature {
@@ -351,6 +443,30 @@ impl<'a> core::iter::Sum<&'a PrivateKey> for PrivateKey {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_signature_canonical() {
+        let key = PrivateKey(UBig::from(123u64));
+        let digest = Digest([Belt(1), Belt(2), Belt(3), Belt(4), Belt(5)]);
+        let sig = key.sign(&digest);
+        assert!(sig.is_canonical());
+
+        let mut unreduced = sig.clone();
+        unreduced.s += &*G_ORDER;
+        assert!(!unreduced.is_canonical());
+        assert!(!key.public_key().verify(&digest, &unreduced));
+
+        unreduced.normalize();
+        assert!(unreduced.is_canonical());
+        assert_eq!(unreduced.s, sig.s);
+        assert!(key.public_key().verify(&digest, &unreduced));
+
+        let negated = Signature {
+            c: sig.c.clone(),
+            s: &*G_ORDER - &sig.s,
+        };
+        assert!(!key.public_key().verify(&digest, &negated));
+    }
+
     #[test]
     fn mupk_test() {
         let privs = [
@@ -431,6 +547,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_batch_verify() {
+        let mut items: Vec<_> = (1..=4u64)
+            .map(|i| {
+                let key = PrivateKey(UBig::from(1000 + i));
+                let digest = Digest([Belt(i), Belt(2), Belt(3), Belt(4), Belt(5)]);
+                (key.public_key(), digest, key.sign(&digest))
+            })
+            .collect();
+        assert!(batch_verify(&items));
+        assert!(batch_verify(&[]));
+
+        items[2].2.s += UBig::from(1u64);
+        assert!(!batch_verify(&items));
+
+        items[2].2 = PrivateKey(UBig::from(1003u64)).sign(&items[2].1);
+        assert!(batch_verify(&items));
+
+        // a valid signature, checked against the wrong key
+        items[3].0 = items[0].0.clone();
+        assert!(!batch_verify(&items));
+    }
+
     #[test]
     fn test_vector() {
         // from nockchain zkvm-jetpack cheetah_jets.rs test_batch_verify_affine
@@ -462,4 +601,83 @@ mod tests {
         };
         assert!(pubkey.verify(&digest, &signature));
     }
+
+    /// `(private scalar hex, pubkey hash)` known answers for [`PublicKey::from_private`].
+    ///
+    /// The third is the key of `test_keygen` in `lib.rs`, derived by the reference wallet.
+    const PUBLIC_KEY_VECTORS: [(&str, &str); 4] = [
+        (
+            "01",
+            "Cogjy7wgp5okmoB9TXjZc51Mki81ynfsNLqEKJkdjFT2JZsn16HnMwC",
+        ),
+        (
+            "7b",
+            "3keGf8JwDxT1Kpxb3zmsqoDY1s3a3nL24bqRu8MonsdQ26WotapJQdZ",
+        ),
+        (
+            "362b4073814e43f427983a83f11efcceb6741082c18f0d64b7e47340ba4485ba",
+            "AyzPiJoqcqmdZdjxZ9aGLnVsbYcCphidHERKBWVXyKhNqTirshTmicG",
+        ),
+        (
+            "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a",
+            "2Pvqux31CnHfWTgy78rEdo8wHDRG5bTWfEGWYhexSJ6YUMcZKPVoknC",
+        ),
+    ];
+
+    #[test]
+    fn test_public_key_vectors() {
+        for (scalar, pkh) in PUBLIC_KEY_VECTORS {
+            let private_key = PrivateKey(UBig::from_str_radix(scalar, 16).unwrap());
+            let public_key = PublicKey::from_private(&private_key);
+            assert_eq!(public_key.hash().to_string(), pkh, "scalar {scalar}");
+            assert_eq!(private_key.public_key(), public_key);
+        }
+        assert_eq!(
+            PublicKey::from_private(&PrivateKey(UBig::from(1u64))).0,
+            A_GEN
+        );
+    }
+
+    #[test]
+    fn test_pubkey_hash_set() {
+        use std::collections::HashSet;
+
+        let pubkey = PrivateKey(UBig::from(123u64)).public_key();
+        let other = PrivateKey(UBig::from(124u64)).public_key();
+
+        let mut set = HashSet::new();
+        assert!(set.insert(pubkey.as_key()));
+        assert!(!set.insert(pubkey.clone().as_key()));
+        assert_eq!(set.len(), 1);
+
+        set.insert(PubkeyHash::from(&other));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&PubkeyHash(pubkey.hash())));
+    }
+
+    #[test]
+    fn test_cmp_canonical() {
+        let keys: Vec<PublicKey> = [123u64, 456, 789]
+            .map(|v| PrivateKey(UBig::from(v)).public_key())
+            .to_vec();
+        assert_eq!(PublicKey::aggregate(&keys), keys.iter().sum());
+
+        let mut sorted = keys.clone();
+        sorted.sort_by(|a, b| a.cmp_canonical(b));
+        for order in [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ] {
+            let mut resorted: Vec<PublicKey> = order.iter().map(|&i| keys[i].clone()).collect();
+            resorted.sort_by(|a, b| a.cmp_canonical(b));
+            assert_eq!(resorted, sorted);
+        }
+        assert!(sorted
+            .windows(2)
+            .all(|w| w[0].to_be_bytes() < w[1].to_be_bytes()));
+    }
 }
@@ -1,14 +1,33 @@
 pub mod cheetah;
 pub mod slip10;
 
-pub use cheetah::{PrivateKey, PublicKey, Signature};
-pub use slip10::{derive_master_key, ExtendedKey};
+pub use cheetah::{batch_verify, PrivateKey, PubkeyHash, PublicKey, Signature};
+pub use slip10::{derive_master_key, ExtendedKey, ExtendedKeyError};
 
 use argon2::{Algorithm, Argon2, Params, Version};
 use bip39::Mnemonic;
 
 /// Generate master key from entropy and salt using Argon2 + BIP39 + SLIP-10
+///
+/// This is the canonical derivation of the nockchain wallet, pinned by the `test_keygen` vector:
+///
+/// 1. Argon2d (v0x13) of `entropy` salted with `salt`, with 768 MiB of memory, 6 iterations,
+///    4 lanes and a 32-byte output.
+/// 2. The output bytes are reversed, matching the byte order of the reference wallet.
+/// 3. The reversed bytes are the BIP39 entropy of a 24-word mnemonic, whose seed (empty
+///    passphrase) is the SLIP-10 master key seed.
+///
+/// Other implementations must match every step, otherwise the same inputs give a different
+/// wallet.
 pub fn gen_master_key(entropy: &[u8], salt: &[u8]) -> (String, ExtendedKey) {
+    let (mnemonic, _, key) = gen_master_key_full(entropy, salt);
+    (mnemonic, key)
+}
+
+/// Same as [`gen_master_key`], but also returns the 64-byte BIP39 seed the key is derived from.
+///
+/// The seed is as sensitive as the private key, callers should wipe it once done with it.
+pub fn gen_master_key_full(entropy: &[u8], salt: &[u8]) -> (String, [u8; 64], ExtendedKey) {
     let mut argon_output = [0u8; 32];
     let params = Params::new(
         786_432,  // m_cost: 768 MiB in KiB
@@ -25,10 +44,22 @@ pub fn gen_master_key(entropy: &[u8], salt: &[u8]) -> (String, ExtendedKey) {
     argon_output.reverse();
 
     let mnemonic = Mnemonic::from_entropy(&argon_output).unwrap();
-    (
-        mnemonic.to_string(),
-        derive_master_key(&mnemonic.to_seed("")),
-    )
+    let seed = mnemonic.to_seed("");
+    (mnemonic.to_string(), seed, derive_master_key(&seed))
+}
+
+/// Generate a random 32-byte entropy and 16-byte salt for [`gen_master_key`], e.g. for a new wallet.
+///
+/// `rng` must be cryptographically secure, such as `rand_core::OsRng`.
+#[cfg(feature = "rand")]
+pub fn generate_entropy_and_salt<R: rand_core::RngCore + rand_core::CryptoRng>(
+    rng: &mut R,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut entropy = vec![0u8; 32];
+    let mut salt = vec![0u8; 16];
+    rng.fill_bytes(&mut entropy);
+    rng.fill_bytes(&mut salt);
+    (entropy, salt)
 }
 
 #[cfg(test)]
@@ -84,4 +115,44 @@ mod tests {
             "AyzPiJoqcqmdZdjxZ9aGLnVsbYcCphidHERKBWVXyKhNqTirshTmicG"
         );
     }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_keygen_generated_entropy_and_salt() {
+        use rand_chacha::rand_core::SeedableRng;
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+        let (entropy, salt) = generate_entropy_and_salt(&mut rng);
+        assert_eq!(entropy.len(), 32);
+        assert_eq!(salt.len(), 16);
+        assert_ne!(
+            generate_entropy_and_salt(&mut rng),
+            (entropy.clone(), salt.clone())
+        );
+
+        let (mnemonic, keypair) = gen_master_key(&entropy, &salt);
+        assert_eq!(Mnemonic::parse(&mnemonic).unwrap().word_count(), 24);
+        assert!(keypair.private_key.is_some());
+    }
+
+    #[test]
+    fn test_keygen_full_seed() {
+        let entropy = [7u8; 32];
+        let salt = [9u8; 16];
+
+        let (mnemonic, seed, keypair) = gen_master_key_full(&entropy, &salt);
+        assert_eq!(
+            seed,
+            Mnemonic::parse(&mnemonic).unwrap().to_seed(""),
+            "seed must match the returned mnemonic"
+        );
+
+        let rederived = derive_master_key(&seed);
+        assert_eq!(rederived.public_key, keypair.public_key);
+        assert_eq!(rederived.chain_code, keypair.chain_code);
+        assert_eq!(
+            rederived.private_key.unwrap().to_be_bytes(),
+            keypair.private_key.unwrap().to_be_bytes()
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use hmac::{Hmac, Mac};
 use ibig::UBig;
 use rose_ztd::crypto::cheetah::{ch_add, ch_scal_big, A_GEN, G_ORDER};
-use sha2::Sha512;
+use rose_ztd::{Hashable, PRIME};
+use sha2::{Digest as _, Sha256, Sha512};
 
 use crate::cheetah::{PrivateKey, PublicKey};
 
@@ -17,9 +18,21 @@ pub struct ExtendedKey {
     pub private_key: Option<PrivateKey>,
     pub public_key: PublicKey,
     pub chain_code: [u8; 32],
+    /// Number of derivations from the master key, which is at depth 0
+    pub depth: u8,
+    /// [`ExtendedKey::fingerprint`] of the parent key, zero for the master key
+    pub parent_fingerprint: [u8; 4],
+    /// Index this key was derived at, zero for the master key
+    pub child_number: u32,
 }
 
 impl ExtendedKey {
+    /// Identifies the key as a parent: the first 4 bytes of its public key hash.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let hash = self.public_key.hash().to_bytes();
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
     /// Derive a child key at the given index using SLIP-10
     pub fn derive_child(&self, index: u32) -> ExtendedKey {
         let hardened = index >= (1 << 31);
@@ -39,6 +52,8 @@ impl ExtendedKey {
             data.extend_from_slice(&index.to_be_bytes());
         }
         let mut result = hmac_sha512(&self.chain_code, &data);
+        let depth = self.depth.saturating_add(1);
+        let parent_fingerprint = self.fingerprint();
 
         loop {
             let left = UBig::from_be_bytes(&result[..32]);
@@ -56,6 +71,9 @@ impl ExtendedKey {
                                 private_key: Some(private_key),
                                 public_key,
                                 chain_code,
+                                depth,
+                                parent_fingerprint,
+                                child_number: index,
                             };
                         }
                     }
@@ -67,6 +85,9 @@ impl ExtendedKey {
                                 private_key: None,
                                 public_key: PublicKey(point),
                                 chain_code,
+                                depth,
+                                parent_fingerprint,
+                                child_number: index,
                             };
                         }
                     }
@@ -82,6 +103,191 @@ impl ExtendedKey {
     }
 }
 
+/// Version prefix of serialized extended private keys, encoding to `nprv…`.
+///
+/// Distinct from the BIP-32 secp256k1 prefixes, so other wallets don't mistake these for
+/// secp256k1 keys.
+pub const XPRV_VERSION: [u8; 4] = [0x03, 0xb8, 0xc4, 0x1f];
+/// Version prefix of serialized extended public keys, encoding to `npub…`.
+pub const XPUB_VERSION: [u8; 4] = [0x2a, 0x2c, 0xe8, 0xf0];
+
+const XKEY_HEADER_LEN: usize = 4 + 1 + 4 + 4 + 32;
+const XPRV_LEN: usize = XKEY_HEADER_LEN + 33;
+const XPUB_LEN: usize = XKEY_HEADER_LEN + 97;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtendedKeyError {
+    InvalidBase58,
+    InvalidChecksum,
+    InvalidLength(usize),
+    InvalidVersion([u8; 4]),
+    InvalidKey,
+    MissingPrivateKey,
+}
+
+impl core::fmt::Display for ExtendedKeyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExtendedKeyError::InvalidBase58 => write!(f, "Invalid base58 string"),
+            ExtendedKeyError::InvalidChecksum => write!(f, "Invalid extended key checksum"),
+            ExtendedKeyError::InvalidLength(len) => {
+                write!(f, "Invalid extended key length: {len}")
+            }
+            ExtendedKeyError::InvalidVersion(v) => {
+                write!(f, "Unexpected extended key version: {v:02x?}")
+            }
+            ExtendedKeyError::InvalidKey => write!(f, "Invalid key data"),
+            ExtendedKeyError::MissingPrivateKey => {
+                write!(f, "Cannot serialize xprv without private key")
+            }
+        }
+    }
+}
+
+fn checksum(data: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(data));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+impl ExtendedKey {
+    /// Serialize as a base58check extended private key.
+    ///
+    /// The layout follows BIP-32 (`version || depth || parent fingerprint || child number ||
+    /// chain code || 0x00 || key`), with a 4-byte double-SHA256 checksum.
+    pub fn to_xprv(&self) -> Result<String, ExtendedKeyError> {
+        let private_key = self
+            .private_key
+            .as_ref()
+            .ok_or(ExtendedKeyError::MissingPrivateKey)?;
+        let mut data = self.xkey_header(XPRV_VERSION);
+        data.push(0x00);
+        data.extend_from_slice(&private_key.to_be_bytes());
+        Ok(Self::encode_check(data))
+    }
+
+    /// Serialize as a base58check extended public key.
+    ///
+    /// Same layout as [`ExtendedKey::to_xprv`], except the key is the 97-byte cheetah public
+    /// key (see [`PublicKey::to_be_bytes`]) instead of the 33-byte secp256k1 one.
+    pub fn to_xpub(&self) -> String {
+        let mut data = self.xkey_header(XPUB_VERSION);
+        data.extend_from_slice(&self.public_key.to_be_bytes());
+        Self::encode_check(data)
+    }
+
+    /// Parse an extended private key produced by [`ExtendedKey::to_xprv`].
+    pub fn from_xprv(s: &str) -> Result<ExtendedKey, ExtendedKeyError> {
+        let (header, key) = Self::decode_check(s, XPRV_VERSION, XPRV_LEN)?;
+        if key[0] != 0x00 {
+            return Err(ExtendedKeyError::InvalidKey);
+        }
+        let s = UBig::from_be_bytes(&key[1..]);
+        if s == UBig::from(0u64) || s >= *G_ORDER {
+            return Err(ExtendedKeyError::InvalidKey);
+        }
+        let private_key = PrivateKey(s);
+        Ok(header.with_key(Some(private_key.clone()), private_key.public_key()))
+    }
+
+    /// Parse an extended public key produced by [`ExtendedKey::to_xpub`].
+    ///
+    /// The key must be a point of the curve, with every coordinate in the field.
+    pub fn from_xpub(s: &str) -> Result<ExtendedKey, ExtendedKeyError> {
+        let (header, key) = Self::decode_check(s, XPUB_VERSION, XPUB_LEN)?;
+        if key[0] != 0x01 {
+            return Err(ExtendedKeyError::InvalidKey);
+        }
+        if key[1..]
+            .chunks(8)
+            .any(|belt| u64::from_be_bytes(belt.try_into().unwrap()) >= PRIME)
+        {
+            return Err(ExtendedKeyError::InvalidKey);
+        }
+        let public_key = PublicKey::from_be_bytes(&key);
+        if !public_key.0.in_curve() {
+            return Err(ExtendedKeyError::InvalidKey);
+        }
+        Ok(header.with_key(None, public_key))
+    }
+
+    /// Drop the private key, keeping only what is needed for public derivation.
+    pub fn neuter(&self) -> ExtendedKey {
+        ExtendedKey {
+            private_key: None,
+            ..self.clone()
+        }
+    }
+
+    fn xkey_header(&self, version: [u8; 4]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(XPUB_LEN + 4);
+        data.extend_from_slice(&version);
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&self.chain_code);
+        data
+    }
+
+    fn encode_check(mut data: Vec<u8>) -> String {
+        let check = checksum(&data);
+        data.extend_from_slice(&check);
+        bs58::encode(data).into_string()
+    }
+
+    fn decode_check(
+        s: &str,
+        version: [u8; 4],
+        len: usize,
+    ) -> Result<(XKeyHeader, Vec<u8>), ExtendedKeyError> {
+        let mut data = bs58::decode(s)
+            .into_vec()
+            .map_err(|_| ExtendedKeyError::InvalidBase58)?;
+        if data.len() < 4 {
+            return Err(ExtendedKeyError::InvalidLength(data.len()));
+        }
+        let check = data.split_off(data.len() - 4);
+        if check != checksum(&data) {
+            return Err(ExtendedKeyError::InvalidChecksum);
+        }
+        if data[..4] != version {
+            return Err(ExtendedKeyError::InvalidVersion([
+                data[0], data[1], data[2], data[3],
+            ]));
+        }
+        if data.len() != len {
+            return Err(ExtendedKeyError::InvalidLength(data.len()));
+        }
+        let header = XKeyHeader {
+            depth: data[4],
+            parent_fingerprint: data[5..9].try_into().unwrap(),
+            child_number: u32::from_be_bytes(data[9..13].try_into().unwrap()),
+            chain_code: data[13..XKEY_HEADER_LEN].try_into().unwrap(),
+        };
+        Ok((header, data.split_off(XKEY_HEADER_LEN)))
+    }
+}
+
+/// Everything of a serialized extended key but the version and the key itself
+struct XKeyHeader {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+}
+
+impl XKeyHeader {
+    fn with_key(self, private_key: Option<PrivateKey>, public_key: PublicKey) -> ExtendedKey {
+        ExtendedKey {
+            private_key,
+            public_key,
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        }
+    }
+}
+
 pub fn derive_master_key(seed: &[u8]) -> ExtendedKey {
     const DOMAIN_SEPARATOR: &[u8] = b"Nockchain seed";
     let mut result = hmac_sha512(DOMAIN_SEPARATOR, seed);
@@ -96,6 +302,9 @@ pub fn derive_master_key(seed: &[u8]) -> ExtendedKey {
                 private_key: Some(private_key),
                 public_key,
                 chain_code,
+                depth: 0,
+                parent_fingerprint: [0; 4],
+                child_number: 0,
             };
         }
         result = hmac_sha512(DOMAIN_SEPARATOR, &result[..]);
@@ -106,7 +315,7 @@ pub fn derive_master_key(seed: &[u8]) -> ExtendedKey {
 mod tests {
     use super::*;
     use bip39::Mnemonic;
-    use rose_ztd::{Belt, Hashable, NounEncode};
+    use rose_ztd::{Belt, NounEncode};
 
     fn from_b58(s: &str) -> Vec<u8> {
         bs58::decode(s).into_vec().unwrap()
@@ -155,6 +364,119 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_xprv_xpub_roundtrip() {
+        let mnemonic = Mnemonic::parse("clutch inmate mango seek attract credit illegal popular term loyal fiber output trumpet lucky garbage merge menu certain dynamic aim trip fantasy master unveil").unwrap();
+        let key = derive_master_key(&mnemonic.to_seed(""));
+
+        let xprv = key.to_xprv().unwrap();
+        let parsed = ExtendedKey::from_xprv(&xprv).unwrap();
+        assert_eq!(
+            parsed.private_key.as_ref().unwrap().0,
+            key.private_key.as_ref().unwrap().0
+        );
+        assert_eq!(parsed.public_key, key.public_key);
+        assert_eq!(parsed.chain_code, key.chain_code);
+
+        // Chain code must survive for hardened derivation to match.
+        let child = parsed.derive_child(1 << 31);
+        let expected = key.derive_child(1 << 31);
+        assert_eq!(
+            child.private_key.unwrap().0,
+            expected.private_key.unwrap().0
+        );
+        assert_eq!(child.chain_code, expected.chain_code);
+
+        let xpub = key.to_xpub();
+        let parsed = ExtendedKey::from_xpub(&xpub).unwrap();
+        assert!(parsed.private_key.is_none());
+        assert_eq!(parsed.public_key, key.public_key);
+        assert_eq!(
+            parsed.derive_child(0).public_key,
+            key.derive_child(0).public_key
+        );
+
+        let mut tampered = bs58::decode(&xprv).into_vec().unwrap();
+        tampered[20] ^= 1;
+        assert_eq!(
+            ExtendedKey::from_xprv(&bs58::encode(tampered).into_string()).unwrap_err(),
+            ExtendedKeyError::InvalidChecksum
+        );
+    }
+
+    #[test]
+    fn test_child_metadata() {
+        let mnemonic = Mnemonic::parse("clutch inmate mango seek attract credit illegal popular term loyal fiber output trumpet lucky garbage merge menu certain dynamic aim trip fantasy master unveil").unwrap();
+        let key = derive_master_key(&mnemonic.to_seed(""));
+        assert_eq!(
+            (key.depth, key.parent_fingerprint, key.child_number),
+            (0, [0; 4], 0)
+        );
+        assert_eq!(key.fingerprint()[..], key.public_key.hash().to_bytes()[..4]);
+
+        let account = key.derive_child((1 << 31) + 7);
+        assert_eq!(account.depth, 1);
+        assert_eq!(account.parent_fingerprint, key.fingerprint());
+        assert_eq!(account.child_number, (1 << 31) + 7);
+
+        let address = account.derive_child(5);
+        assert_eq!(address.depth, 2);
+        assert_eq!(address.parent_fingerprint, account.fingerprint());
+        assert_eq!(address.child_number, 5);
+        // public derivation tracks the same metadata
+        let public = account.neuter().derive_child(5);
+        assert_eq!(public.parent_fingerprint, address.parent_fingerprint);
+        assert_eq!((public.depth, public.child_number), (2, 5));
+
+        // and it survives serialization
+        let parsed = ExtendedKey::from_xprv(&address.to_xprv().unwrap()).unwrap();
+        assert_eq!(
+            (parsed.depth, parsed.parent_fingerprint, parsed.child_number),
+            (2, account.fingerprint(), 5)
+        );
+        let parsed = ExtendedKey::from_xpub(&public.to_xpub()).unwrap();
+        assert_eq!(
+            (parsed.depth, parsed.parent_fingerprint, parsed.child_number),
+            (2, account.fingerprint(), 5)
+        );
+    }
+
+    #[test]
+    fn test_xpub_rejects_invalid_key() {
+        let mnemonic = Mnemonic::parse("clutch inmate mango seek attract credit illegal popular term loyal fiber output trumpet lucky garbage merge menu certain dynamic aim trip fantasy master unveil").unwrap();
+        let key = derive_master_key(&mnemonic.to_seed(""));
+        assert!(key.to_xprv().unwrap().starts_with("nprv"));
+        let xpub = key.to_xpub();
+        assert!(xpub.starts_with("npub"));
+
+        let tamper = |f: &dyn Fn(&mut [u8])| {
+            let mut data = bs58::decode(&xpub).into_vec().unwrap();
+            data.truncate(data.len() - 4);
+            f(&mut data[XKEY_HEADER_LEN..]);
+            ExtendedKey::from_xpub(&ExtendedKey::encode_check(data)).unwrap_err()
+        };
+        // out of field coordinate
+        assert_eq!(
+            tamper(&|key| key[1..9].copy_from_slice(&u64::MAX.to_be_bytes())),
+            ExtendedKeyError::InvalidKey
+        );
+        // in the field, but off the curve
+        assert_eq!(tamper(&|key| key[8] ^= 1), ExtendedKeyError::InvalidKey);
+    }
+
+    #[test]
+    fn test_neutered_key_is_not_xprv() {
+        let mnemonic = Mnemonic::parse("clutch inmate mango seek attract credit illegal popular term loyal fiber output trumpet lucky garbage merge menu certain dynamic aim trip fantasy master unveil").unwrap();
+        let key = derive_master_key(&mnemonic.to_seed("")).neuter();
+
+        assert_eq!(key.to_xprv(), Err(ExtendedKeyError::MissingPrivateKey));
+        let xpub = key.to_xpub();
+        assert_eq!(
+            ExtendedKey::from_xprv(&xpub).unwrap_err(),
+            ExtendedKeyError::InvalidVersion(XPUB_VERSION)
+        );
+    }
+
     #[test]
     fn test_nockchain_message_vector() {
         // Test vector from: nockchain-wallet sign-message "hello"
@@ -1,8 +1,13 @@
 // Client module is only available for native targets (not WASM)
 #![cfg(not(target_arch = "wasm32"))]
 
+use std::time::Duration;
+
 use rose_nockchain_types::{BalanceUpdate, RawTx, TxId};
-use tonic::transport::Channel;
+use rose_ztd::Digest;
+#[cfg(feature = "tls")]
+use tonic::transport::ClientTlsConfig;
+use tonic::transport::{Channel, Endpoint};
 
 use crate::pb::common::v1::{Base58Hash, Base58Pubkey, PageRequest};
 use crate::pb::common::{v1 as pb_common_v1, v2 as pb_common_v2};
@@ -35,6 +40,7 @@ pub enum ClientError {
 #[derive(Clone)]
 pub struct PublicNockchainGrpcClient {
     client: NockchainServiceClient<Channel>,
+    max_noun_depth: Option<usize>,
 }
 
 pub enum BalanceRequest {
@@ -42,10 +48,169 @@ pub enum BalanceRequest {
     FirstName(String),
 }
 
+/// Status of a submitted transaction, see [`PublicNockchainGrpcClient::transaction_status`].
+///
+/// The public API only reports whether the node accepted a transaction. Confirmations are not
+/// exposed yet (`TransactionConfirmation` is commented out in the service), so there is no
+/// confirmed state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TxStatus {
+    /// Accepted by the node, and not known to have been dropped
+    Pending,
+    /// Not known to the node: never submitted, rejected or dropped
+    Unknown,
+}
+
+/// Channel configuration for [`PublicNockchainGrpcClient`].
+///
+/// ```no_run
+/// # async fn run() -> rose_grpc_proto::client::Result<()> {
+/// use std::time::Duration;
+/// use rose_grpc_proto::client::PublicNockchainGrpcClient;
+///
+/// let client = PublicNockchainGrpcClient::builder("http://localhost:50051")
+///     .connect_timeout(Duration::from_secs(5))
+///     .request_timeout(Duration::from_secs(30))
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PublicNockchainGrpcClientBuilder {
+    endpoint: String,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    max_noun_depth: Option<usize>,
+    #[cfg(feature = "tls")]
+    tls_config: Option<ClientTlsConfig>,
+}
+
+impl PublicNockchainGrpcClientBuilder {
+    pub fn new<T: Into<String>>(endpoint: T) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            connect_timeout: None,
+            request_timeout: None,
+            tcp_keepalive: None,
+            max_noun_depth: None,
+            #[cfg(feature = "tls")]
+            tls_config: None,
+        }
+    }
+
+    pub fn endpoint<T: Into<String>>(&mut self, endpoint: T) -> &mut Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Timeout for establishing the connection
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each request, including any (re)connection it triggers
+    pub fn request_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    pub fn tcp_keepalive(&mut self, interval: Duration) -> &mut Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Reject balances carrying note-data values deeper than `depth`, see
+    /// [`crate::convert::check_noun_depth`]. Unlimited by default.
+    pub fn max_noun_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_noun_depth = Some(depth);
+        self
+    }
+
+    #[cfg(feature = "tls")]
+    pub fn tls_config(&mut self, tls_config: ClientTlsConfig) -> &mut Self {
+        self.tls_config = Some(tls_config);
+        self
+    }
+
+    pub fn get_endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn get_connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    pub fn get_request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    pub fn get_tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive
+    }
+
+    pub fn get_max_noun_depth(&self) -> Option<usize> {
+        self.max_noun_depth
+    }
+
+    /// Tonic endpoint with all of the configuration applied.
+    pub fn to_endpoint(&self) -> std::result::Result<Endpoint, tonic::transport::Error> {
+        let mut endpoint =
+            Endpoint::from_shared(self.endpoint.clone())?.tcp_keepalive(self.tcp_keepalive);
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        #[cfg(feature = "tls")]
+        if let Some(tls_config) = &self.tls_config {
+            endpoint = endpoint.tls_config(tls_config.clone())?;
+        }
+        Ok(endpoint)
+    }
+
+    pub async fn connect(&self) -> Result<PublicNockchainGrpcClient> {
+        let channel = self.to_endpoint()?.connect().await?;
+        Ok(PublicNockchainGrpcClient {
+            max_noun_depth: self.max_noun_depth,
+            ..PublicNockchainGrpcClient::from_channel(channel)
+        })
+    }
+
+    /// Create the client without connecting. The connection is made on the first request.
+    pub fn connect_lazy(
+        &self,
+    ) -> std::result::Result<PublicNockchainGrpcClient, tonic::transport::Error> {
+        let channel = self.to_endpoint()?.connect_lazy();
+        Ok(PublicNockchainGrpcClient {
+            max_noun_depth: self.max_noun_depth,
+            ..PublicNockchainGrpcClient::from_channel(channel)
+        })
+    }
+}
+
 impl PublicNockchainGrpcClient {
     pub async fn connect<T: AsRef<str>>(address: T) -> Result<Self> {
         let client = NockchainServiceClient::connect(address.as_ref().to_string()).await?;
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            max_noun_depth: None,
+        })
+    }
+
+    pub fn builder<T: Into<String>>(endpoint: T) -> PublicNockchainGrpcClientBuilder {
+        PublicNockchainGrpcClientBuilder::new(endpoint)
+    }
+
+    pub fn from_channel(channel: Channel) -> Self {
+        Self {
+            client: NockchainServiceClient::new(channel),
+            max_noun_depth: None,
+        }
     }
 
     pub async fn wallet_get_balance(&mut self, request: &BalanceRequest) -> Result<BalanceUpdate> {
@@ -95,6 +260,7 @@ impl PublicNockchainGrpcClient {
                 return Err(ClientError::SnapshotChanged);
             }
 
+            #[allow(clippy::useless_conversion)]
             all_notes.extend(balance.notes.into_iter());
             page_token = balance
                 .page
@@ -121,7 +287,19 @@ impl PublicNockchainGrpcClient {
             }),
         };
 
-        Ok(pb_balance.try_into()?)
+        let update: BalanceUpdate = pb_balance.try_into()?;
+        if let Some(max_depth) = self.max_noun_depth {
+            crate::convert::check_noun_depth(
+                update
+                    .notes
+                    .0
+                    .iter()
+                    .flat_map(|(_, note)| &note.note_data.entries)
+                    .map(|entry| &entry.val),
+                max_depth,
+            )?;
+        }
+        Ok(update)
     }
 
     pub async fn wallet_send_transaction(&mut self, raw_tx: &RawTx) -> Result<TxId> {
@@ -158,11 +336,157 @@ impl PublicNockchainGrpcClient {
             .into_inner();
 
         match response.result {
-            Some(transaction_accepted_response::Result::Accepted(_)) => Ok(true),
+            Some(transaction_accepted_response::Result::Accepted(accepted)) => Ok(accepted),
             Some(transaction_accepted_response::Result::Error(err)) => {
                 Err(ClientError::ServerError(err.message))
             }
             None => Err(ClientError::EmptyResponse),
         }
     }
+
+    /// Poll the status of a transaction submitted with
+    /// [`PublicNockchainGrpcClient::wallet_send_transaction`].
+    pub async fn transaction_status(&mut self, id: Digest) -> Result<TxStatus> {
+        let accepted = self
+            .transaction_accepted(Base58Hash {
+                hash: id.to_string(),
+            })
+            .await?;
+        Ok(if accepted {
+            TxStatus::Pending
+        } else {
+            TxStatus::Unknown
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_builder_config() {
+        let mut builder = PublicNockchainGrpcClient::builder("http://localhost:50051");
+        assert_eq!(builder.get_connect_timeout(), None);
+        builder
+            .endpoint("http://127.0.0.1:50052")
+            .connect_timeout(Duration::from_secs(3))
+            .request_timeout(Duration::from_secs(10))
+            .tcp_keepalive(Duration::from_secs(60));
+        assert_eq!(builder.get_endpoint(), "http://127.0.0.1:50052");
+        assert_eq!(builder.get_connect_timeout(), Some(Duration::from_secs(3)));
+        assert_eq!(builder.get_request_timeout(), Some(Duration::from_secs(10)));
+        assert_eq!(builder.get_tcp_keepalive(), Some(Duration::from_secs(60)));
+        assert_eq!(
+            builder.to_endpoint().unwrap().uri().to_string(),
+            "http://127.0.0.1:50052/"
+        );
+
+        builder.endpoint("not a uri");
+        assert!(builder.to_endpoint().is_err());
+    }
+
+    struct MockService;
+
+    #[tonic::async_trait]
+    impl nockchain_service_server::NockchainService for MockService {
+        async fn wallet_get_balance(
+            &self,
+            _: tonic::Request<WalletGetBalanceRequest>,
+        ) -> std::result::Result<tonic::Response<WalletGetBalanceResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("mock"))
+        }
+
+        async fn wallet_send_transaction(
+            &self,
+            _: tonic::Request<WalletSendTransactionRequest>,
+        ) -> std::result::Result<tonic::Response<WalletSendTransactionResponse>, tonic::Status>
+        {
+            Err(tonic::Status::unimplemented("mock"))
+        }
+
+        async fn transaction_accepted(
+            &self,
+            request: tonic::Request<TransactionAcceptedRequest>,
+        ) -> std::result::Result<tonic::Response<TransactionAcceptedResponse>, tonic::Status>
+        {
+            use transaction_accepted_response::Result as R;
+            let id: Digest = request
+                .into_inner()
+                .tx_id
+                .unwrap()
+                .hash
+                .as_str()
+                .try_into()
+                .unwrap();
+            let result = match id.0[0].0 {
+                1 => R::Accepted(true),
+                2 => R::Accepted(false),
+                _ => R::Error(pb_common_v1::ErrorStatus {
+                    code: 3,
+                    message: "bad tx id".into(),
+                    details: None,
+                }),
+            };
+            Ok(tonic::Response::new(TransactionAcceptedResponse {
+                result: Some(result),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_status() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming =
+            tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(nockchain_service_server::NockchainServiceServer::new(
+                    MockService,
+                ))
+                .serve_with_incoming(incoming),
+        );
+
+        let mut client = PublicNockchainGrpcClient::builder(format!("http://{addr}"))
+            .connect()
+            .await
+            .unwrap();
+        let id = |first: u64| Digest::from([first, 2, 3, 4, 5]);
+        assert_eq!(
+            client.transaction_status(id(1)).await.unwrap(),
+            TxStatus::Pending
+        );
+        assert_eq!(
+            client.transaction_status(id(2)).await.unwrap(),
+            TxStatus::Unknown
+        );
+        assert!(matches!(
+            client.transaction_status(id(3)).await,
+            Err(ClientError::ServerError(message)) if message == "bad tx id"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_applied() {
+        // Accepts TCP connections, but never speaks HTTP/2.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut client = PublicNockchainGrpcClient::builder(format!("http://{addr}"))
+            .request_timeout(Duration::from_millis(200))
+            .connect_lazy()
+            .unwrap();
+
+        let start = Instant::now();
+        let res = client
+            .transaction_accepted(Base58Hash {
+                hash: String::new(),
+            })
+            .await;
+        assert!(res.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
 }
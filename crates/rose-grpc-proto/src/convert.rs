@@ -1,12 +1,12 @@
 use rose_nockchain_types::*;
-use rose_ztd::{jam, Belt, Digest, Noun};
+use rose_ztd::{belts_to_ubig, jam, ubig_to_belts, Belt, Digest, Noun};
 
 use crate::common::{ConversionError, Required};
 use crate::pb::common::v1::{
-    BlockHeight as PbBlockHeight, Hash as PbHash, Name as PbName, Nicks as PbNicks,
-    NoteVersion as PbNoteVersion, SchnorrSignature as PbSchnorrSignature,
-    Signature as PbLegacySignature, SignatureEntry as PbSignatureEntry, Source as PbSource,
-    TimeLockRangeAbsolute as PbTimeLockRangeAbsolute,
+    BlockHeight as PbBlockHeight, EightBelt as PbEightBelt, Hash as PbHash, Name as PbName,
+    Nicks as PbNicks, NoteVersion as PbNoteVersion, SchnorrSignature as PbSchnorrSignature,
+    Signature as PbLegacySignature, SignatureEntry as PbSignatureEntry, SixBelt as PbSixBelt,
+    Source as PbSource, TimeLockRangeAbsolute as PbTimeLockRangeAbsolute,
     TimeLockRangeRelative as PbTimeLockRangeRelative,
 };
 use crate::pb::common::v1::{
@@ -24,6 +24,24 @@ use crate::pb::common::v2::{
     SpendEntry as PbSpendEntry, Witness as PbWitness, WitnessSpend as PbWitnessSpend,
 };
 
+/// Check that none of `nouns` is deeper than `max_depth`, before anything hashes or encodes
+/// them recursively.
+///
+/// Jammed nouns decoded from protobuf (note-data values, hax preimages) are not limited by the
+/// conversions themselves: memos are lists with one cell per byte, so any fixed limit would
+/// reject valid notes. Use this to opt in to a limit on untrusted data, e.g. with
+/// [`crate::client::PublicNockchainGrpcClientBuilder::max_noun_depth`].
+pub fn check_noun_depth<'a>(
+    nouns: impl IntoIterator<Item = &'a Noun>,
+    max_depth: usize,
+) -> Result<(), ConversionError> {
+    if nouns.into_iter().any(|noun| noun.depth() > max_depth) {
+        Err(ConversionError::Invalid("noun too deep"))
+    } else {
+        Ok(())
+    }
+}
+
 // =========================
 // Primitive type conversions
 // =========================
@@ -40,6 +58,79 @@ impl From<crate::pb::common::v1::Belt> for Belt {
     }
 }
 
+impl From<[Belt; 6]> for PbSixBelt {
+    fn from(b: [Belt; 6]) -> Self {
+        let [belt_1, belt_2, belt_3, belt_4, belt_5, belt_6] = b.map(|v| Some(v.into()));
+        PbSixBelt {
+            belt_1,
+            belt_2,
+            belt_3,
+            belt_4,
+            belt_5,
+            belt_6,
+        }
+    }
+}
+
+impl From<&[Belt; 6]> for PbSixBelt {
+    fn from(b: &[Belt; 6]) -> Self {
+        PbSixBelt::from(*b)
+    }
+}
+
+impl TryFrom<PbSixBelt> for [Belt; 6] {
+    type Error = ConversionError;
+    fn try_from(b: PbSixBelt) -> Result<Self, Self::Error> {
+        Ok([
+            b.belt_1.required("SixBelt", "belt_1")?.into(),
+            b.belt_2.required("SixBelt", "belt_2")?.into(),
+            b.belt_3.required("SixBelt", "belt_3")?.into(),
+            b.belt_4.required("SixBelt", "belt_4")?.into(),
+            b.belt_5.required("SixBelt", "belt_5")?.into(),
+            b.belt_6.required("SixBelt", "belt_6")?.into(),
+        ])
+    }
+}
+
+impl From<[Belt; 8]> for PbEightBelt {
+    fn from(b: [Belt; 8]) -> Self {
+        let [belt_1, belt_2, belt_3, belt_4, belt_5, belt_6, belt_7, belt_8] =
+            b.map(|v| Some(v.into()));
+        PbEightBelt {
+            belt_1,
+            belt_2,
+            belt_3,
+            belt_4,
+            belt_5,
+            belt_6,
+            belt_7,
+            belt_8,
+        }
+    }
+}
+
+impl From<&[Belt; 8]> for PbEightBelt {
+    fn from(b: &[Belt; 8]) -> Self {
+        PbEightBelt::from(*b)
+    }
+}
+
+impl TryFrom<PbEightBelt> for [Belt; 8] {
+    type Error = ConversionError;
+    fn try_from(b: PbEightBelt) -> Result<Self, Self::Error> {
+        Ok([
+            b.belt_1.required("EightBelt", "belt_1")?.into(),
+            b.belt_2.required("EightBelt", "belt_2")?.into(),
+            b.belt_3.required("EightBelt", "belt_3")?.into(),
+            b.belt_4.required("EightBelt", "belt_4")?.into(),
+            b.belt_5.required("EightBelt", "belt_5")?.into(),
+            b.belt_6.required("EightBelt", "belt_6")?.into(),
+            b.belt_7.required("EightBelt", "belt_7")?.into(),
+            b.belt_8.required("EightBelt", "belt_8")?.into(),
+        ])
+    }
+}
+
 impl<T: Into<Digest>> From<T> for PbHash {
     fn from(h: T) -> Self {
         let h = h.into();
@@ -197,7 +288,7 @@ impl From<PbTimeLockRangeRelative> for TimelockRange {
 impl From<Seed> for PbSeed {
     fn from(seed: Seed) -> Self {
         PbSeed {
-            output_source: None, // nbx types don't track output source
+            output_source: seed.output_source.map(PbSource::from),
             lock_root: Some(PbHash::from(seed.lock_root)),
             note_data: Some(PbNoteData::from(seed.note_data)),
             gift: Some(PbNicks::from(seed.gift)),
@@ -306,100 +397,17 @@ impl From<LockMerkleProof> for PbLockMerkleProof {
 
 impl From<PkhSignature> for PbPkhSignature {
     fn from(signature: PkhSignature) -> Self {
-        use rose_ztd::Belt as ZBelt;
-
         PbPkhSignature {
             entries: signature
                 .0
                 .into_iter()
-                .map(|(pkh, pubkey, sig)| {
-                    // Convert UBig to Belt arrays for c and s
-                    let c_bytes = sig.c.to_le_bytes();
-                    let s_bytes = sig.s.to_le_bytes();
-                    let c_belts = ZBelt::from_bytes(&c_bytes);
-                    let s_belts = ZBelt::from_bytes(&s_bytes);
-
-                    // Pad to 8 belts
-                    let mut chal = [0u64; 8];
-                    for (i, belt) in c_belts.iter().take(8).enumerate() {
-                        chal[i] = belt.0;
-                    }
-                    let mut sig_val = [0u64; 8];
-                    for (i, belt) in s_belts.iter().take(8).enumerate() {
-                        sig_val[i] = belt.0;
-                    }
-
-                    crate::pb::common::v2::PkhSignatureEntry {
+                .map(
+                    |(pkh, pubkey, sig)| crate::pb::common::v2::PkhSignatureEntry {
                         hash: Some(PbHash::from(pkh)),
-                        pubkey: Some(crate::pb::common::v1::SchnorrPubkey {
-                            value: Some(crate::pb::common::v1::CheetahPoint {
-                                x: Some(crate::pb::common::v1::SixBelt {
-                                    belt_1: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[0].0,
-                                    }),
-                                    belt_2: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[1].0,
-                                    }),
-                                    belt_3: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[2].0,
-                                    }),
-                                    belt_4: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[3].0,
-                                    }),
-                                    belt_5: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[4].0,
-                                    }),
-                                    belt_6: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.x.0[5].0,
-                                    }),
-                                }),
-                                y: Some(crate::pb::common::v1::SixBelt {
-                                    belt_1: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[0].0,
-                                    }),
-                                    belt_2: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[1].0,
-                                    }),
-                                    belt_3: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[2].0,
-                                    }),
-                                    belt_4: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[3].0,
-                                    }),
-                                    belt_5: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[4].0,
-                                    }),
-                                    belt_6: Some(crate::pb::common::v1::Belt {
-                                        value: pubkey.0.y.0[5].0,
-                                    }),
-                                }),
-                                inf: pubkey.0.inf,
-                            }),
-                        }),
-                        signature: Some(crate::pb::common::v1::SchnorrSignature {
-                            chal: Some(crate::pb::common::v1::EightBelt {
-                                belt_1: Some(crate::pb::common::v1::Belt { value: chal[0] }),
-                                belt_2: Some(crate::pb::common::v1::Belt { value: chal[1] }),
-                                belt_3: Some(crate::pb::common::v1::Belt { value: chal[2] }),
-                                belt_4: Some(crate::pb::common::v1::Belt { value: chal[3] }),
-                                belt_5: Some(crate::pb::common::v1::Belt { value: chal[4] }),
-                                belt_6: Some(crate::pb::common::v1::Belt { value: chal[5] }),
-                                belt_7: Some(crate::pb::common::v1::Belt { value: chal[6] }),
-                                belt_8: Some(crate::pb::common::v1::Belt { value: chal[7] }),
-                            }),
-                            sig: Some(crate::pb::common::v1::EightBelt {
-                                belt_1: Some(crate::pb::common::v1::Belt { value: sig_val[0] }),
-                                belt_2: Some(crate::pb::common::v1::Belt { value: sig_val[1] }),
-                                belt_3: Some(crate::pb::common::v1::Belt { value: sig_val[2] }),
-                                belt_4: Some(crate::pb::common::v1::Belt { value: sig_val[3] }),
-                                belt_5: Some(crate::pb::common::v1::Belt { value: sig_val[4] }),
-                                belt_6: Some(crate::pb::common::v1::Belt { value: sig_val[5] }),
-                                belt_7: Some(crate::pb::common::v1::Belt { value: sig_val[6] }),
-                                belt_8: Some(crate::pb::common::v1::Belt { value: sig_val[7] }),
-                            }),
-                        }),
-                    }
-                })
+                        pubkey: Some(public_key_to_pb(pubkey)),
+                        signature: Some(schnorr_sig_to_pb(sig)),
+                    },
+                )
                 .collect(),
         }
     }
@@ -409,11 +417,6 @@ impl TryFrom<PbPkhSignature> for PkhSignature {
     type Error = ConversionError;
 
     fn try_from(pb: PbPkhSignature) -> Result<Self, Self::Error> {
-        use ibig::UBig;
-        use rose_crypto::{PublicKey, Signature};
-        use rose_ztd::crypto::cheetah::{CheetahPoint, F6lt};
-        use rose_ztd::Belt as ZBelt;
-
         let entries = pb
             .entries
             .into_iter()
@@ -422,71 +425,12 @@ impl TryFrom<PbPkhSignature> for PkhSignature {
                     .hash
                     .required("PkhSignatureEntry", "hash")?
                     .try_into()?;
-
-                let pubkey_pb = entry
-                    .pubkey
-                    .required("PkhSignatureEntry", "pubkey")?
-                    .value
-                    .required("SchnorrPubkey", "value")?;
-
-                let x_pb = pubkey_pb.x.required("CheetahPoint", "x")?;
-                let y_pb = pubkey_pb.y.required("CheetahPoint", "y")?;
-
-                let pubkey = PublicKey(CheetahPoint {
-                    x: F6lt([
-                        ZBelt(x_pb.belt_1.required("SixBelt", "belt_1")?.value),
-                        ZBelt(x_pb.belt_2.required("SixBelt", "belt_2")?.value),
-                        ZBelt(x_pb.belt_3.required("SixBelt", "belt_3")?.value),
-                        ZBelt(x_pb.belt_4.required("SixBelt", "belt_4")?.value),
-                        ZBelt(x_pb.belt_5.required("SixBelt", "belt_5")?.value),
-                        ZBelt(x_pb.belt_6.required("SixBelt", "belt_6")?.value),
-                    ]),
-                    y: F6lt([
-                        ZBelt(y_pb.belt_1.required("SixBelt", "belt_1")?.value),
-                        ZBelt(y_pb.belt_2.required("SixBelt", "belt_2")?.value),
-                        ZBelt(y_pb.belt_3.required("SixBelt", "belt_3")?.value),
-                        ZBelt(y_pb.belt_4.required("SixBelt", "belt_4")?.value),
-                        ZBelt(y_pb.belt_5.required("SixBelt", "belt_5")?.value),
-                        ZBelt(y_pb.belt_6.required("SixBelt", "belt_6")?.value),
-                    ]),
-                    inf: pubkey_pb.inf,
-                });
-
-                let sig_pb = entry.signature.required("PkhSignatureEntry", "signature")?;
-                let chal_pb = sig_pb.chal.required("SchnorrSignature", "chal")?;
-                let sig_val_pb = sig_pb.sig.required("SchnorrSignature", "sig")?;
-
-                // Collect belt values into arrays
-                let chal_belts = [
-                    chal_pb.belt_1.required("EightBelt", "belt_1")?.value,
-                    chal_pb.belt_2.required("EightBelt", "belt_2")?.value,
-                    chal_pb.belt_3.required("EightBelt", "belt_3")?.value,
-                    chal_pb.belt_4.required("EightBelt", "belt_4")?.value,
-                    chal_pb.belt_5.required("EightBelt", "belt_5")?.value,
-                    chal_pb.belt_6.required("EightBelt", "belt_6")?.value,
-                    chal_pb.belt_7.required("EightBelt", "belt_7")?.value,
-                    chal_pb.belt_8.required("EightBelt", "belt_8")?.value,
-                ];
-                let sig_belts = [
-                    sig_val_pb.belt_1.required("EightBelt", "belt_1")?.value,
-                    sig_val_pb.belt_2.required("EightBelt", "belt_2")?.value,
-                    sig_val_pb.belt_3.required("EightBelt", "belt_3")?.value,
-                    sig_val_pb.belt_4.required("EightBelt", "belt_4")?.value,
-                    sig_val_pb.belt_5.required("EightBelt", "belt_5")?.value,
-                    sig_val_pb.belt_6.required("EightBelt", "belt_6")?.value,
-                    sig_val_pb.belt_7.required("EightBelt", "belt_7")?.value,
-                    sig_val_pb.belt_8.required("EightBelt", "belt_8")?.value,
-                ];
-
-                // Convert belt arrays to UBig
-                let c_vec: Vec<ZBelt> = chal_belts.iter().map(|v| ZBelt(*v)).collect();
-                let s_vec: Vec<ZBelt> = sig_belts.iter().map(|v| ZBelt(*v)).collect();
-
-                let c = UBig::from_le_bytes(&ZBelt::to_bytes(&c_vec));
-                let s = UBig::from_le_bytes(&ZBelt::to_bytes(&s_vec));
-
-                let signature = Signature { c, s };
-
+                let pubkey = pb_schnorr_pubkey_to_public_key(
+                    entry.pubkey.required("PkhSignatureEntry", "pubkey")?,
+                )?;
+                let signature = pb_schnorr_sig_to_sig(
+                    entry.signature.required("PkhSignatureEntry", "signature")?,
+                )?;
                 Ok((pkh, pubkey, signature))
             })
             .collect::<Result<Vec<_>, ConversionError>>()?;
@@ -498,91 +442,26 @@ impl TryFrom<PbPkhSignature> for PkhSignature {
 fn public_key_to_pb(pubkey: rose_crypto::PublicKey) -> PbSchnorrPubkey {
     PbSchnorrPubkey {
         value: Some(crate::pb::common::v1::CheetahPoint {
-            x: Some(crate::pb::common::v1::SixBelt {
-                belt_1: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[0].0,
-                }),
-                belt_2: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[1].0,
-                }),
-                belt_3: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[2].0,
-                }),
-                belt_4: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[3].0,
-                }),
-                belt_5: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[4].0,
-                }),
-                belt_6: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.x.0[5].0,
-                }),
-            }),
-            y: Some(crate::pb::common::v1::SixBelt {
-                belt_1: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[0].0,
-                }),
-                belt_2: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[1].0,
-                }),
-                belt_3: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[2].0,
-                }),
-                belt_4: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[3].0,
-                }),
-                belt_5: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[4].0,
-                }),
-                belt_6: Some(crate::pb::common::v1::Belt {
-                    value: pubkey.0.y.0[5].0,
-                }),
-            }),
+            x: Some(pubkey.0.x.0.into()),
+            y: Some(pubkey.0.y.0.into()),
             inf: pubkey.0.inf,
         }),
     }
 }
 
-fn schnorr_sig_to_pb(sig: rose_crypto::Signature) -> PbSchnorrSignature {
-    use rose_ztd::Belt as ZBelt;
-
-    // Convert UBig to Belt arrays for c and s
-    let c_bytes = sig.c.to_le_bytes();
-    let s_bytes = sig.s.to_le_bytes();
-    let c_belts = ZBelt::from_bytes(&c_bytes);
-    let s_belts = ZBelt::from_bytes(&s_bytes);
+/// Pack a signature scalar into 8 belts of 32-bit little-endian limbs.
+fn ubig_to_eight_belt(v: &ibig::UBig) -> [Belt; 8] {
+    ubig_to_belts(v, 8).try_into().unwrap()
+}
 
-    // Pad to 8 belts
-    let mut chal = [0u64; 8];
-    for (i, belt) in c_belts.iter().take(8).enumerate() {
-        chal[i] = belt.0;
-    }
-    let mut sig_val = [0u64; 8];
-    for (i, belt) in s_belts.iter().take(8).enumerate() {
-        sig_val[i] = belt.0;
-    }
+fn eight_belt_to_ubig(belts: [Belt; 8]) -> ibig::UBig {
+    belts_to_ubig(&belts)
+}
 
+fn schnorr_sig_to_pb(sig: rose_crypto::Signature) -> PbSchnorrSignature {
     PbSchnorrSignature {
-        chal: Some(crate::pb::common::v1::EightBelt {
-            belt_1: Some(crate::pb::common::v1::Belt { value: chal[0] }),
-            belt_2: Some(crate::pb::common::v1::Belt { value: chal[1] }),
-            belt_3: Some(crate::pb::common::v1::Belt { value: chal[2] }),
-            belt_4: Some(crate::pb::common::v1::Belt { value: chal[3] }),
-            belt_5: Some(crate::pb::common::v1::Belt { value: chal[4] }),
-            belt_6: Some(crate::pb::common::v1::Belt { value: chal[5] }),
-            belt_7: Some(crate::pb::common::v1::Belt { value: chal[6] }),
-            belt_8: Some(crate::pb::common::v1::Belt { value: chal[7] }),
-        }),
-        sig: Some(crate::pb::common::v1::EightBelt {
-            belt_1: Some(crate::pb::common::v1::Belt { value: sig_val[0] }),
-            belt_2: Some(crate::pb::common::v1::Belt { value: sig_val[1] }),
-            belt_3: Some(crate::pb::common::v1::Belt { value: sig_val[2] }),
-            belt_4: Some(crate::pb::common::v1::Belt { value: sig_val[3] }),
-            belt_5: Some(crate::pb::common::v1::Belt { value: sig_val[4] }),
-            belt_6: Some(crate::pb::common::v1::Belt { value: sig_val[5] }),
-            belt_7: Some(crate::pb::common::v1::Belt { value: sig_val[6] }),
-            belt_8: Some(crate::pb::common::v1::Belt { value: sig_val[7] }),
-        }),
+        chal: Some(ubig_to_eight_belt(&sig.c).into()),
+        sig: Some(ubig_to_eight_belt(&sig.s).into()),
     }
 }
 
@@ -590,29 +469,17 @@ fn pb_schnorr_pubkey_to_public_key(
     pb: PbSchnorrPubkey,
 ) -> Result<rose_crypto::PublicKey, ConversionError> {
     use rose_ztd::crypto::cheetah::{CheetahPoint, F6lt};
-    use rose_ztd::Belt as ZBelt;
 
     let pt = pb.value.required("SchnorrPubkey", "value")?;
-    let x_pb = pt.x.required("CheetahPoint", "x")?;
-    let y_pb = pt.y.required("CheetahPoint", "y")?;
-
+    // The identity is never a valid public key.
+    if pt.inf {
+        return Err(ConversionError::Invalid(
+            "public key is the point at infinity",
+        ));
+    }
     Ok(rose_crypto::PublicKey(CheetahPoint {
-        x: F6lt([
-            ZBelt(x_pb.belt_1.required("SixBelt", "belt_1")?.value),
-            ZBelt(x_pb.belt_2.required("SixBelt", "belt_2")?.value),
-            ZBelt(x_pb.belt_3.required("SixBelt", "belt_3")?.value),
-            ZBelt(x_pb.belt_4.required("SixBelt", "belt_4")?.value),
-            ZBelt(x_pb.belt_5.required("SixBelt", "belt_5")?.value),
-            ZBelt(x_pb.belt_6.required("SixBelt", "belt_6")?.value),
-        ]),
-        y: F6lt([
-            ZBelt(y_pb.belt_1.required("SixBelt", "belt_1")?.value),
-            ZBelt(y_pb.belt_2.required("SixBelt", "belt_2")?.value),
-            ZBelt(y_pb.belt_3.required("SixBelt", "belt_3")?.value),
-            ZBelt(y_pb.belt_4.required("SixBelt", "belt_4")?.value),
-            ZBelt(y_pb.belt_5.required("SixBelt", "belt_5")?.value),
-            ZBelt(y_pb.belt_6.required("SixBelt", "belt_6")?.value),
-        ]),
+        x: F6lt(pt.x.required("CheetahPoint", "x")?.try_into()?),
+        y: F6lt(pt.y.required("CheetahPoint", "y")?.try_into()?),
         inf: pt.inf,
     }))
 }
@@ -620,39 +487,8 @@ fn pb_schnorr_pubkey_to_public_key(
 fn pb_schnorr_sig_to_sig(
     pb: PbSchnorrSignature,
 ) -> Result<rose_crypto::Signature, ConversionError> {
-    use ibig::UBig;
-    use rose_ztd::Belt as ZBelt;
-
-    let chal_pb = pb.chal.required("SchnorrSignature", "chal")?;
-    let sig_val_pb = pb.sig.required("SchnorrSignature", "sig")?;
-
-    let chal_belts = [
-        chal_pb.belt_1.required("EightBelt", "belt_1")?.value,
-        chal_pb.belt_2.required("EightBelt", "belt_2")?.value,
-        chal_pb.belt_3.required("EightBelt", "belt_3")?.value,
-        chal_pb.belt_4.required("EightBelt", "belt_4")?.value,
-        chal_pb.belt_5.required("EightBelt", "belt_5")?.value,
-        chal_pb.belt_6.required("EightBelt", "belt_6")?.value,
-        chal_pb.belt_7.required("EightBelt", "belt_7")?.value,
-        chal_pb.belt_8.required("EightBelt", "belt_8")?.value,
-    ];
-    let sig_belts = [
-        sig_val_pb.belt_1.required("EightBelt", "belt_1")?.value,
-        sig_val_pb.belt_2.required("EightBelt", "belt_2")?.value,
-        sig_val_pb.belt_3.required("EightBelt", "belt_3")?.value,
-        sig_val_pb.belt_4.required("EightBelt", "belt_4")?.value,
-        sig_val_pb.belt_5.required("EightBelt", "belt_5")?.value,
-        sig_val_pb.belt_6.required("EightBelt", "belt_6")?.value,
-        sig_val_pb.belt_7.required("EightBelt", "belt_7")?.value,
-        sig_val_pb.belt_8.required("EightBelt", "belt_8")?.value,
-    ];
-
-    let c_vec: Vec<ZBelt> = chal_belts.iter().map(|v| ZBelt(*v)).collect();
-    let s_vec: Vec<ZBelt> = sig_belts.iter().map(|v| ZBelt(*v)).collect();
-
-    let c = UBig::from_le_bytes(&ZBelt::to_bytes(&c_vec));
-    let s = UBig::from_le_bytes(&ZBelt::to_bytes(&s_vec));
-
+    let c = eight_belt_to_ubig(pb.chal.required("SchnorrSignature", "chal")?.try_into()?);
+    let s = eight_belt_to_ubig(pb.sig.required("SchnorrSignature", "sig")?.try_into()?);
     Ok(rose_crypto::Signature { c, s })
 }
 
@@ -846,39 +682,8 @@ impl TryFrom<PbNote> for Note {
                 assets: v1.assets.required("NoteV1", "assets")?.into(),
             }),
             crate::pb::common::v2::note::NoteVersion::Legacy(legacy) => {
-                use rose_crypto::PublicKey;
-                use rose_ztd::crypto::cheetah::{CheetahPoint, F6lt};
-                use rose_ztd::Belt as ZBelt;
                 use rose_ztd::Hashable;
 
-                fn pb_schnorr_pubkey_to_public_key(
-                    pb: PbSchnorrPubkey,
-                ) -> Result<PublicKey, ConversionError> {
-                    let pt = pb.value.required("SchnorrPubkey", "value")?;
-                    let x_pb = pt.x.required("CheetahPoint", "x")?;
-                    let y_pb = pt.y.required("CheetahPoint", "y")?;
-
-                    Ok(PublicKey(CheetahPoint {
-                        x: F6lt([
-                            ZBelt(x_pb.belt_1.required("SixBelt", "belt_1")?.value),
-                            ZBelt(x_pb.belt_2.required("SixBelt", "belt_2")?.value),
-                            ZBelt(x_pb.belt_3.required("SixBelt", "belt_3")?.value),
-                            ZBelt(x_pb.belt_4.required("SixBelt", "belt_4")?.value),
-                            ZBelt(x_pb.belt_5.required("SixBelt", "belt_5")?.value),
-                            ZBelt(x_pb.belt_6.required("SixBelt", "belt_6")?.value),
-                        ]),
-                        y: F6lt([
-                            ZBelt(y_pb.belt_1.required("SixBelt", "belt_1")?.value),
-                            ZBelt(y_pb.belt_2.required("SixBelt", "belt_2")?.value),
-                            ZBelt(y_pb.belt_3.required("SixBelt", "belt_3")?.value),
-                            ZBelt(y_pb.belt_4.required("SixBelt", "belt_4")?.value),
-                            ZBelt(y_pb.belt_5.required("SixBelt", "belt_5")?.value),
-                            ZBelt(y_pb.belt_6.required("SixBelt", "belt_6")?.value),
-                        ]),
-                        inf: pt.inf,
-                    }))
-                }
-
                 fn tim_from_intent(intent: PbTimeLockIntent) -> Option<LockTim> {
                     use crate::pb::common::v1::time_lock_intent;
 
@@ -1097,6 +902,7 @@ impl TryFrom<PbRawTransaction> for RawTx {
                                 }
                                 map
                             },
+                            // Reserved, not carried by protobuf (field 4 is commented out).
                             tim: (),
                         };
 
@@ -1139,6 +945,109 @@ impl TryFrom<PbRawTransaction> for RawTx {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_belt_array_roundtrip() {
+        let six = [1, 2, 3, 4, 5, 6].map(Belt);
+        let pb = PbSixBelt::from(six);
+        assert_eq!(pb.belt_6.unwrap().value, 6);
+        assert_eq!(<[Belt; 6]>::try_from(pb).unwrap(), six);
+
+        let eight = [1, 2, 3, 4, 5, 6, 7, 8].map(Belt);
+        let pb = PbEightBelt::from(eight);
+        assert_eq!(pb.belt_1.unwrap().value, 1);
+        assert_eq!(pb.belt_8.unwrap().value, 8);
+        assert_eq!(<[Belt; 8]>::try_from(pb).unwrap(), eight);
+
+        let mut missing = PbEightBelt::from(eight);
+        missing.belt_5 = None;
+        assert!(<[Belt; 8]>::try_from(missing).is_err());
+
+        assert_eq!(PbSixBelt::from(&six), PbSixBelt::from(six));
+        assert_eq!(PbEightBelt::from(&eight), PbEightBelt::from(eight));
+    }
+
+    #[test]
+    fn test_hash_from_borrowed_digest() {
+        let digest = Digest::from([1, 2, 3, 4, 5]);
+        assert_eq!(PbHash::from(&digest), PbHash::from(digest));
+        assert_eq!(Digest::try_from(PbHash::from(&digest)).unwrap(), digest);
+    }
+
+    #[test]
+    fn test_infinity_pubkey_rejected() {
+        let public_key = rose_crypto::PublicKey(rose_ztd::crypto::cheetah::A_GEN);
+        let pb = public_key_to_pb(public_key.clone());
+        assert_eq!(pb_schnorr_pubkey_to_public_key(pb).unwrap(), public_key);
+
+        let mut inf = pb;
+        inf.value.as_mut().unwrap().inf = true;
+        assert!(matches!(
+            pb_schnorr_pubkey_to_public_key(inf),
+            Err(ConversionError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_long_memo_noun_depth() {
+        use rose_ztd::Hashable;
+
+        // A memo is a list with one cell per byte, so its depth is its length. Varied bytes keep
+        // jam's backreference search cheap.
+        let memo: String = (0..2000).map(|i: u32| i.to_string()).collect::<String>()[..4000].into();
+        let mut note_data = NoteData::empty();
+        note_data.push_memo_utf8(&memo);
+        let note = Note::new(
+            Version::V1,
+            13,
+            Name::new(
+                Digest::from([1, 2, 3, 4, 5]),
+                Digest::from([6, 7, 8, 9, 10]),
+            ),
+            note_data,
+            1000,
+        );
+        let decoded = Note::try_from(PbNote::from(note.clone())).unwrap();
+        assert_eq!(decoded.hash(), note.hash());
+
+        let memo = decoded.note_data.memo().unwrap();
+        assert!(memo.depth() >= 4000);
+        assert!(check_noun_depth([memo], 8192).is_ok());
+        assert!(matches!(
+            check_noun_depth([memo], 1024),
+            Err(ConversionError::Invalid("noun too deep"))
+        ));
+    }
+
+    #[test]
+    fn test_seed_output_source_roundtrip() {
+        let lock = SpendCondition::new_pkh(Pkh::single(Digest::from([1, 2, 3, 4, 5])));
+        let note = Note::new(
+            Version::V1,
+            13,
+            Name::new(lock.first_name(), Digest::from([6, 7, 8, 9, 10])),
+            NoteData::empty(),
+            4294967296,
+        );
+        let spend = SpendBuilder::new(note, lock.clone(), None);
+        let source = Source {
+            hash: Digest::from([11, 12, 13, 14, 15]),
+            is_coinbase: true,
+        };
+        let seed = spend.build_seed_with_source(lock, 1234, false, source.clone());
+
+        let decoded = Seed::try_from(PbSeed::from(seed.clone())).unwrap();
+        let decoded_source = decoded.output_source.unwrap();
+        assert_eq!(decoded_source.hash, source.hash);
+        assert!(decoded_source.is_coinbase);
+        assert_eq!(decoded.gift, seed.gift);
+
+        let seed = spend.build_seed(SpendCondition::new_pkh(Pkh::single(source.hash)), 1, false);
+        assert!(Seed::try_from(PbSeed::from(seed))
+            .unwrap()
+            .output_source
+            .is_none());
+    }
+
     #[test]
     fn decode_raw_tx() {
         let json = r#"{
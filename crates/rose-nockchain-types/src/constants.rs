@@ -0,0 +1,42 @@
+//! Well-known values of the tx engine.
+
+use rose_ztd::{Belt, Digest};
+
+use crate::{LockTim, TimelockRange};
+
+/// Digest of all-zero belts
+pub const ZERO_DIGEST: Digest = Digest([Belt(0); 5]);
+
+/// Note-data key of the memo entry
+pub const MEMO_KEY: &str = "memo";
+
+/// Note-data key of the lock entry (spend condition of the note)
+pub const LOCK_KEY: &str = "lock";
+
+/// Minimum relative timelock of coinbase notes, in blocks
+pub const COINBASE_TIMELOCK_MIN: u64 = 100;
+
+/// Timelock of coinbase notes
+pub const COINBASE_LOCK_TIM: LockTim = LockTim {
+    rel: TimelockRange {
+        min: Some(COINBASE_TIMELOCK_MIN),
+        max: None,
+    },
+    abs: TimelockRange {
+        min: None,
+        max: None,
+    },
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rose_ztd::Hashable;
+
+    #[test]
+    fn test_coinbase_lock_tim() {
+        assert_eq!(LockTim::coinbase(), COINBASE_LOCK_TIM);
+        assert_eq!(LockTim::coinbase().hash(), COINBASE_LOCK_TIM.hash());
+        assert_eq!(ZERO_DIGEST, Digest::from([0; 5]));
+    }
+}
@@ -1,7 +1,11 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod constants;
 mod tx_engine;
 
+pub use constants::{LOCK_KEY, MEMO_KEY};
 pub use tx_engine::*;
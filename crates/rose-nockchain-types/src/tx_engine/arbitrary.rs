@@ -0,0 +1,160 @@
+//! [`Arbitrary`] impls for fuzzing, behind the `arbitrary` feature.
+//!
+//! Generated locks are well-formed (see [`SpendCondition::validate`]), so fuzz targets reach
+//! past validation.
+
+use alloc::vec::Vec;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use rose_ztd::Digest;
+
+use super::note::{BlockHeight, Name, Note, NoteData, Pkh, Source, TimelockRange, Version};
+use super::tx::{Hax, LockPrimitive, LockRoot, LockTim, Seed, SpendCondition};
+
+fn digests(u: &mut Unstructured<'_>, max: usize) -> Result<Vec<Digest>> {
+    let n = u.int_in_range(1..=max)?;
+    (0..n).map(|_| u.arbitrary()).collect()
+}
+
+impl<'a> Arbitrary<'a> for Version {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(u.choose(&[Version::V0, Version::V1, Version::V2])?.clone())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Name {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Name::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Source {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Source {
+            hash: u.arbitrary()?,
+            is_coinbase: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Pkh {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let hashes = digests(u, 3)?;
+        let m = u.int_in_range(1..=hashes.len() as u64)?;
+        Ok(Pkh::new(m, hashes))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TimelockRange {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (mut min, mut max): (Option<BlockHeight>, Option<BlockHeight>) = u.arbitrary()?;
+        if let (Some(lo), Some(hi)) = (&mut min, &mut max) {
+            if lo > hi {
+                core::mem::swap(lo, hi);
+            }
+        }
+        Ok(TimelockRange::new(min, max))
+    }
+}
+
+impl<'a> Arbitrary<'a> for LockTim {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(LockTim::try_new(u.arbitrary()?, u.arbitrary()?).expect("ranges are ordered"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Hax {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Hax(digests(u, 2)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for LockPrimitive {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=3)? {
+            0 => LockPrimitive::Pkh(u.arbitrary()?),
+            1 => LockPrimitive::Tim(u.arbitrary()?),
+            2 => LockPrimitive::Hax(u.arbitrary()?),
+            _ => LockPrimitive::Brn,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SpendCondition {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let n = u.int_in_range(1..=3)?;
+        Ok(SpendCondition(
+            (0..n).map(|_| u.arbitrary()).collect::<Result<_>>()?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for LockRoot {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary()? {
+            LockRoot::Lock(u.arbitrary()?)
+        } else {
+            LockRoot::Hash(u.arbitrary()?)
+        })
+    }
+}
+
+/// Optionally a lock and a UTF-8 memo, the entries the wallet itself writes.
+impl<'a> Arbitrary<'a> for NoteData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut note_data = NoteData::empty();
+        if u.arbitrary()? {
+            note_data.push_lock(u.arbitrary()?);
+        }
+        if u.arbitrary()? {
+            note_data.push_memo_utf8(u.arbitrary()?);
+        }
+        Ok(note_data)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Seed {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Seed {
+            output_source: u.arbitrary()?,
+            lock_root: u.arbitrary()?,
+            note_data: u.arbitrary()?,
+            gift: u.arbitrary()?,
+            parent_hash: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Note {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Note::new(
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rose_ztd::{cue, jam, Hashable, NounDecode, NounEncode};
+
+    #[test]
+    fn test_arbitrary_spend_condition_valid() {
+        let bytes: Vec<u8> = (0..1 << 14)
+            .map(|i: u32| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..200 {
+            let spend_condition = SpendCondition::arbitrary(&mut u).unwrap();
+            assert!(spend_condition.validate().is_ok());
+            let noun = cue(&jam(spend_condition.to_noun())).unwrap();
+            assert_eq!(
+                SpendCondition::from_noun(&noun).map(|v| v.hash()),
+                Some(spend_condition.hash())
+            );
+        }
+    }
+}
@@ -1,19 +1,25 @@
 use alloc::collections::btree_map::BTreeMap;
 use alloc::collections::btree_set::BTreeSet;
+use alloc::format;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
-use rose_crypto::PrivateKey;
-use rose_ztd::{noun_deserialize, noun_serialize, Digest, Hashable as HashableTrait, Noun, ZSet};
+use core::ops::Deref;
+use rose_crypto::{PrivateKey, PublicKey, Signature};
+use rose_ztd::{
+    noun_deserialize, noun_serialize, Digest, Hashable as HashableTrait, Noun, ZMap, ZSet,
+};
 use serde::{Deserialize, Serialize};
 
 use super::note::Note;
 use super::tx::{
-    LockRoot, NockchainTx, Seed, Seeds, Spend, SpendCondition, Spends, TransactionDisplay, Witness,
+    LockClause, LockPrimitive, LockRoot, NockchainTx, Seed, Seeds, Spend, SpendCondition, Spends,
+    TransactionDisplay, Witness,
 };
-use super::{Name, NoteData, Version};
+use super::{Balance, Name, NoteData, NoteDataEntry, Source, Version};
 use crate::{Nicks, Pkh, RawTx};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum MissingUnlocks {
     Pkh {
         num_sigs: u64,
@@ -107,6 +113,20 @@ impl SpendBuilder {
         self
     }
 
+    /// Like [`SpendBuilder::fee`], but fails with [`BuildError::InsufficientFunds`], leaving the
+    /// fee unchanged, if the note can't pay `fee_portion` on top of its gifts.
+    ///
+    /// The refund seed is not counted as a gift, as it is rebuilt by
+    /// [`SpendBuilder::compute_refund`], which should follow.
+    pub fn try_fee(&mut self, fee_portion: Nicks) -> Result<&mut Self, BuildError> {
+        let refund_gift = self.cur_refund().map_or(0, |v| v.gift);
+        let gifts: Nicks = self.spend.seeds().0.iter().map(|v| v.gift).sum::<Nicks>() - refund_gift;
+        if fee_portion > self.note.assets.saturating_sub(gifts) {
+            return Err(BuildError::InsufficientFunds);
+        }
+        Ok(self.fee(fee_portion))
+    }
+
     pub fn compute_refund(&mut self, include_lock_data: bool) -> &mut Self {
         if self.refund_lock.is_some() {
             self.invalidate_sigs();
@@ -121,13 +141,7 @@ impl SpendBuilder {
                 .0
                 .iter()
                 .find(|v| v.lock_root.hash() == lock_root.hash())
-                .and_then(|seed| {
-                    seed.note_data
-                        .entries
-                        .iter()
-                        .find(|e| e.key == crate::MEMO_KEY)
-                        .map(|e| e.val.clone())
-                });
+                .and_then(|seed| seed.note_data.memo().cloned());
 
             // Remove the previous refund
             self.spend
@@ -149,6 +163,77 @@ impl SpendBuilder {
         self
     }
 
+    /// Remove the `%lock` note-data entry from the refund seed, keeping any other entries (such
+    /// as the memo). Signatures are invalidated if anything was removed.
+    ///
+    /// The sender already knows their refund lock, so the lock data only costs fees. The fee is
+    /// not adjusted, see [`TxBuilder::strip_refund_lock_data`].
+    pub fn strip_refund_lock_data(&mut self) -> &mut Self {
+        let Some(refund_root) = self.refund_lock.as_ref().map(|v| v.hash()) else {
+            return self;
+        };
+        let has_lock_data =
+            |seed: &Seed| seed.lock_root.hash() == refund_root && seed.note_data.has_lock();
+        if !self.spend.seeds().0.iter().any(has_lock_data) {
+            return self;
+        }
+
+        self.invalidate_sigs();
+        for seed in self.spend.seeds_mut().0.iter_mut() {
+            if seed.lock_root.hash() == refund_root {
+                seed.note_data.entries.retain(|e| e.key != crate::LOCK_KEY);
+            }
+        }
+        self
+    }
+
+    /// Change where the refund of this spend goes.
+    ///
+    /// The current refund seed is replaced by one targeting `lock` (keeping any memo on it), and
+    /// signatures are invalidated. Removing the refund lock is only possible if there is no value
+    /// left over to refund, otherwise [`BuildError::UnbalancedSpends`] is returned.
+    pub fn set_refund_lock(
+        &mut self,
+        lock: Option<SpendCondition>,
+        include_lock_data: bool,
+    ) -> Result<&mut Self, BuildError> {
+        let old_refund = self.cur_refund().cloned();
+        let non_refund_gifts = self.spend.seeds().0.iter().map(|v| v.gift).sum::<Nicks>()
+            - old_refund.as_ref().map(|v| v.gift).unwrap_or(0);
+        if lock.is_none() && self.note.assets > non_refund_gifts + self.spend.fee() {
+            return Err(BuildError::UnbalancedSpends);
+        }
+
+        self.invalidate_sigs();
+        if let Some(old_refund) = &old_refund {
+            let old_root = old_refund.lock_root.hash();
+            self.spend
+                .seeds_mut()
+                .0
+                .retain(|v| v.lock_root.hash() != old_root);
+        }
+        self.refund_lock = lock;
+        self.compute_refund(include_lock_data);
+
+        let memo = old_refund.and_then(|seed| seed.note_data.memo().cloned());
+        if let Some(memo) = memo {
+            let new_root = self.refund_lock.as_ref().map(|v| v.hash());
+            if let Some(seed) = self
+                .spend
+                .seeds_mut()
+                .0
+                .iter_mut()
+                .find(|v| Some(v.lock_root.hash()) == new_root)
+            {
+                if seed.note_data.memo().is_none() {
+                    seed.note_data.push_memo(memo);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
     pub fn cur_refund(&self) -> Option<&Seed> {
         let rl = self.refund_lock.as_ref()?;
         let lock_root = LockRoot::Lock(rl.clone());
@@ -180,17 +265,63 @@ impl SpendBuilder {
         }
     }
 
+    /// Like [`SpendBuilder::build_seed`], but with the output source set, e.g. to mark outputs
+    /// derived from a coinbase.
+    pub fn build_seed_with_source(
+        &self,
+        lock: SpendCondition,
+        gift: Nicks,
+        include_lock_data: bool,
+        source: Source,
+    ) -> Seed {
+        let mut seed = self.build_seed(lock, gift, include_lock_data);
+        seed.output_source = Some(source);
+        seed
+    }
+
     pub fn seed(&mut self, seed: Seed) -> &mut Self {
         self.invalidate_sigs();
         self.spend.seeds_mut().0.push(seed);
         self
     }
 
+    /// Attach a custom note-data entry to the seed at `seed_index`, replacing any entry with the
+    /// same key. Signatures are invalidated.
+    ///
+    /// The extra words change the fee, so it needs to be recomputed (see
+    /// [`TxBuilder::set_seed_note_data`]). Note that only the memo survives refund recomputation,
+    /// so custom entries should not be placed on the refund seed.
+    pub fn set_seed_note_data(
+        &mut self,
+        seed_index: usize,
+        key: String,
+        val: Noun,
+    ) -> Result<(), BuildError> {
+        let seed = self
+            .spend
+            .seeds_mut()
+            .0
+            .get_mut(seed_index)
+            .ok_or(BuildError::SeedNotFound(seed_index))?;
+        seed.note_data.entries.retain(|e| e.key != key);
+        seed.note_data.entries.push(NoteDataEntry { key, val });
+        self.invalidate_sigs();
+        Ok(())
+    }
+
     pub fn invalidate_sigs(&mut self) -> &mut Self {
         self.spend.clear_signatures();
         self
     }
 
+    /// Remove all hashlock preimages attached to this spend.
+    pub fn clear_preimages(&mut self) -> &mut Self {
+        if let Spend::Witness(ws) = &mut self.spend {
+            ws.witness.hax_map = ZMap::new();
+        }
+        self
+    }
+
     pub fn missing_unlocks(&self) -> Vec<MissingUnlocks> {
         let mut missing_unlocks = vec![];
 
@@ -280,39 +411,442 @@ impl SpendBuilder {
         false
     }
 
+    /// Attach an externally produced signature, if `public_key` may sign this spend and the
+    /// signature is valid over its sig hash.
+    pub fn attach_signature(&mut self, public_key: PublicKey, signature: Signature) -> bool {
+        let pkh = public_key.hash();
+        let allowed = self.spend_condition.pkh().any(|p| p.hashes.contains(&pkh));
+        if !allowed
+            || self.spend.signer_hashes().contains(&pkh)
+            || !public_key.verify(&self.spend.sig_hash(), &signature)
+        {
+            return false;
+        }
+        self.spend.add_signature(public_key, signature);
+        true
+    }
+
     fn unclamped_fee(&self, fee_per_word: Nicks) -> Nicks {
-        let mut fee = self.spend.unclamped_fee(fee_per_word);
+        self.spend.unclamped_fee(fee_per_word) + self.estimated_unlock_words() * fee_per_word
+    }
+
+    /// Estimated number of words that missing unlocks will add, once attached.
+    fn estimated_unlock_words(&self) -> u64 {
+        let mut words = 0;
 
         for mu in self.missing_unlocks() {
             #[allow(clippy::single_match)]
             match mu {
                 MissingUnlocks::Pkh { num_sigs, .. } => {
                     // Heuristic for missing signatures. It is perhaps 30, but perhaps not.
-                    fee += 35 * num_sigs * fee_per_word;
+                    words += 35 * num_sigs;
                 }
                 // TODO: handle hax
                 _ => (),
             }
         }
 
-        fee
+        words
+    }
+
+    /// Components of the fee needed for this spend.
+    pub fn fee_breakdown(&self, fee_per_word: Nicks) -> InputFee {
+        let (seed_words, witness_words) = self.spend.calc_words();
+        let unlock_words = self.estimated_unlock_words();
+        InputFee {
+            name: self.note.name.clone(),
+            seed_words,
+            witness_words,
+            unlock_words,
+            fee: (seed_words + witness_words + unlock_words) * fee_per_word,
+        }
+    }
+}
+
+/// Fee components of a single spend, see [`SpendBuilder::fee_breakdown`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputFee {
+    pub name: Name,
+    /// Words of note-data in the spend's seeds
+    pub seed_words: u64,
+    /// Words of the signature/witness currently attached to the spend
+    pub witness_words: u64,
+    /// Estimated words of unlocks that are still missing
+    pub unlock_words: u64,
+    /// Fee for all of the above, before clamping to the minimum fee
+    pub fee: Nicks,
+}
+
+/// Projected result of building a transaction, see [`TxBuilder::dry_run`].
+#[derive(Clone, Debug)]
+pub struct DryRun {
+    pub outputs: Vec<Note>,
+    pub fee: Nicks,
+    pub cur_fee: Nicks,
+    pub missing_unlocks: Vec<MissingUnlocks>,
+    pub balanced: bool,
+}
+
+/// Report of a transaction being built, see [`TxBuilder::describe`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TxReport {
+    pub inputs: Vec<InputReport>,
+    pub outputs: Vec<OutputReport>,
+    /// Fee currently set on the spends
+    pub fee: Nicks,
+    /// Fee needed for the transaction, see [`TxBuilder::calc_fee`]
+    pub needed_fee: Nicks,
+    pub balanced: bool,
+    /// Unlocks still missing, only for spends that have any
+    pub missing_unlocks: Vec<(Name, Vec<MissingUnlocks>)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputReport {
+    pub name: Name,
+    pub assets: Nicks,
+    pub lock: Vec<LockClause>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OutputReport {
+    pub name: Name,
+    pub lock_root: Digest,
+    /// Lock requirements, if the full lock is known to the builder
+    pub lock: Option<Vec<LockClause>>,
+    pub gift: Nicks,
+    pub has_memo: bool,
+    pub has_lock_data: bool,
+}
+
+/// Warning about a transaction being built, see [`TxBuilder::lints`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxLint {
+    /// The output's lock is more than a single pkh, but is not embedded in the output's
+    /// note-data. The recipient may be unable to reconstruct the lock, and thus spend the output.
+    MissingLockDataForComplexOutput { lock_hash: Digest },
+    /// The fee rate is zero, so the fee is floored to the minimum and relays may reject the
+    /// transaction.
+    ZeroFeeRate,
+    /// The fee is more than a tenth of the value of the inputs.
+    ExcessiveFee { fee: Nicks, inputs_value: Nicks },
+    /// The refund of a spend goes to a condition that is not among the wallet's own conditions
+    /// (see [`TxBuilder::set_own_conditions`]), so the change may be unrecoverable.
+    RefundNotSelfSpendable { name: Name, lock_hash: Digest },
+}
+
+/// What a co-signer needs to sign a transaction, see [`TxBuilder::export_for_signing`].
+///
+/// Unlike the full builder, this leaves out the fee pool and the input notes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignableTx {
+    pub spends: Vec<SignableSpend>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignableSpend {
+    pub name: Name,
+    pub sig_hash: Digest,
+    #[serde(
+        serialize_with = "noun_serialize",
+        deserialize_with = "noun_deserialize"
+    )]
+    pub spend: Spend,
+    #[serde(
+        serialize_with = "noun_serialize",
+        deserialize_with = "noun_deserialize"
+    )]
+    pub spend_condition: SpendCondition,
+}
+
+impl SignableTx {
+    /// Sign every spend `signing_key` may sign, returning detached signatures to be passed to
+    /// [`TxBuilder::attach_signature`].
+    ///
+    /// Sig hashes are recomputed from the spends, rather than trusting the exported ones.
+    pub fn sign(&self, signing_key: &PrivateKey) -> Vec<(PublicKey, Signature)> {
+        let public_key = signing_key.public_key();
+        let pkh = public_key.hash();
+        self.spends
+            .iter()
+            .filter(|s| s.spend_condition.pkh().any(|p| p.hashes.contains(&pkh)))
+            .map(|s| (public_key.clone(), signing_key.sign(&s.spend.sig_hash())))
+            .collect()
+    }
+}
+
+/// Where the change of a simple spend goes, see [`TxBuilder::simple_spend_with_change`].
+///
+/// Change outputs are public, and link to whatever else uses the same lock. Sending change back
+/// to an input's lock, or reusing a refund address across transactions, lets observers tie
+/// those together. For privacy, send change to a freshly derived address that is not used by any
+/// input or recipient.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangePolicy {
+    /// Single-signature lock of this pkh
+    ToAddress(Digest),
+    /// Lock of the first input note. Convenient, but links the change to that input.
+    ToFirstInput,
+    /// Burn lock, destroying the change
+    Burn,
+}
+
+/// Order in which [`Balance::simulate_spend`] hands notes to [`TxBuilder::simple_spend`], which
+/// pays the gift from the first notes and keeps the others for fees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoinSelection {
+    /// Balance order
+    InOrder,
+    /// Fewest inputs
+    LargestFirst,
+    /// Consolidates small notes
+    SmallestFirst,
+}
+
+/// Projected outcome of a simple spend, see [`Balance::simulate_spend`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendPlan {
+    /// Notes that would be spent, including those only pulled in to pay the fee
+    pub inputs: Vec<Name>,
+    /// Total assets of `inputs`
+    pub selected: Nicks,
+    pub fee: Nicks,
+    pub change: Nicks,
+}
+
+impl Balance {
+    /// Preview a [`TxBuilder::simple_spend`] of `gift` from this balance, without lock data,
+    /// with notes ordered by `selection`. Nothing is mutated.
+    ///
+    /// Notes without lock data are skipped, as their spend condition is unknown.
+    pub fn simulate_spend(
+        &self,
+        gift: Nicks,
+        fee_per_word: Nicks,
+        selection: CoinSelection,
+    ) -> Result<SpendPlan, BuildError> {
+        let mut notes: Vec<(Note, SpendCondition)> = self
+            .0
+            .iter()
+            .filter_map(|(_, note)| {
+                let lock = note.note_data.extract_lock().ok()??;
+                Some((note.clone(), lock))
+            })
+            .collect();
+        match selection {
+            CoinSelection::InOrder => {}
+            CoinSelection::LargestFirst => notes.sort_by_key(|v| core::cmp::Reverse(v.0.assets)),
+            CoinSelection::SmallestFirst => notes.sort_by_key(|v| v.0.assets),
+        }
+
+        // Fees only depend on the number of words, not on the recipient and refund addresses,
+        // as long as they differ.
+        let mut builder = TxBuilder::new(fee_per_word);
+        builder.simple_spend(
+            notes,
+            Digest::from([0, 0, 0, 0, 0]),
+            gift,
+            Digest::from([1, 0, 0, 0, 0]),
+            false,
+            None,
+        )?;
+
+        Ok(SpendPlan {
+            inputs: builder.spends.keys().cloned().collect(),
+            selected: builder.spends.values().map(|v| v.note.assets).sum(),
+            fee: builder.cur_fee(),
+            change: builder.total_change(),
+        })
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// Version of the serialized [`TxBuilder`] layout, bumped on incompatible changes.
+///
+/// Builders serialize as `{ version, builder }`, and deserializing rejects unknown versions.
+/// The unversioned `{ spends, fee_pool, fee_per_word }` maps written before versioning are
+/// still accepted as v1.
+pub const TX_BUILDER_VERSION: u32 = 1;
+
+#[derive(Clone)]
 pub struct TxBuilder {
+    spends: BuilderSpends,
+    fee_pool: Vec<SpendBuilder>,
+    fee_per_word: Nicks,
+    /// Hashes of the conditions the wallet can spend, see [`TxBuilder::set_own_conditions`].
+    own_conditions: BTreeSet<Digest>,
+}
+
+/// Spends of a [`TxBuilder`], along with the cached result of [`TxBuilder::calc_fee`].
+///
+/// Reading goes through `Deref`, and [`BuilderSpends::as_map_mut`], the only way to modify the
+/// spends, clears the cached fee. The fee also depends on `fee_per_word`, which is only set when
+/// the builder is created.
+#[derive(Clone, Default)]
+struct BuilderSpends {
+    spends: BTreeMap<Name, SpendBuilder>,
+    #[cfg(feature = "std")]
+    fee: std::sync::OnceLock<Nicks>,
+    #[cfg(not(feature = "std"))]
+    fee: core::cell::OnceCell<Nicks>,
+}
+
+impl BuilderSpends {
+    fn as_map_mut(&mut self) -> &mut BTreeMap<Name, SpendBuilder> {
+        self.fee.take();
+        &mut self.spends
+    }
+
+    fn into_inner(self) -> BTreeMap<Name, SpendBuilder> {
+        self.spends
+    }
+}
+
+impl Deref for BuilderSpends {
+    type Target = BTreeMap<Name, SpendBuilder>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.spends
+    }
+}
+
+impl From<BTreeMap<Name, SpendBuilder>> for BuilderSpends {
+    fn from(spends: BTreeMap<Name, SpendBuilder>) -> Self {
+        Self {
+            spends,
+            fee: Default::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TxBuilderV1Ref<'a> {
+    spends: &'a BTreeMap<Name, SpendBuilder>,
+    fee_pool: &'a Vec<SpendBuilder>,
+    fee_per_word: Nicks,
+}
+
+#[derive(Deserialize)]
+struct TxBuilderV1 {
     spends: BTreeMap<Name, SpendBuilder>,
     fee_pool: Vec<SpendBuilder>,
     fee_per_word: Nicks,
 }
 
+impl From<TxBuilderV1> for TxBuilder {
+    fn from(v: TxBuilderV1) -> Self {
+        Self {
+            spends: v.spends.into(),
+            fee_pool: v.fee_pool,
+            fee_per_word: v.fee_per_word,
+            own_conditions: BTreeSet::new(),
+        }
+    }
+}
+
+impl Serialize for TxBuilder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("TxBuilder", 2)?;
+        s.serialize_field("version", &TX_BUILDER_VERSION)?;
+        s.serialize_field(
+            "builder",
+            &TxBuilderV1Ref {
+                spends: &self.spends.spends,
+                fee_pool: &self.fee_pool,
+                fee_per_word: self.fee_per_word,
+            },
+        )?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TxBuilder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+        fn check_version<E: de::Error>(version: u32) -> Result<(), E> {
+            if version == TX_BUILDER_VERSION {
+                Ok(())
+            } else {
+                Err(E::custom(format!(
+                    "unsupported TxBuilder version {version}, expected {TX_BUILDER_VERSION}"
+                )))
+            }
+        }
+
+        struct EnvelopeVisitor;
+
+        impl<'de> Visitor<'de> for EnvelopeVisitor {
+            type Value = TxBuilder;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("a versioned TxBuilder")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TxBuilder, A::Error> {
+                let version: u32 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                check_version(version)?;
+                let builder: TxBuilderV1 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                Ok(builder.into())
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TxBuilder, A::Error> {
+                // Keys may come in any order (e.g. sorted, which puts `builder` first), so the
+                // version is checked once the whole map has been read. Builders saved before
+                // versioning are the bare `{ spends, fee_pool, fee_per_word }` layout of v1.
+                let mut version = None;
+                let mut builder: Option<TxBuilderV1> = None;
+                let mut spends = None;
+                let mut fee_pool = None;
+                let mut fee_per_word = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "version" => version = Some(map.next_value::<u32>()?),
+                        "builder" => builder = Some(map.next_value()?),
+                        "spends" => spends = Some(map.next_value()?),
+                        "fee_pool" => fee_pool = Some(map.next_value()?),
+                        "fee_per_word" => fee_per_word = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                match (version, builder) {
+                    (Some(version), builder) => {
+                        check_version(version)?;
+                        Ok(builder
+                            .ok_or_else(|| de::Error::missing_field("builder"))?
+                            .into())
+                    }
+                    (None, Some(_)) => Err(de::Error::missing_field("version")),
+                    (None, None) => Ok(TxBuilderV1 {
+                        spends: spends.ok_or_else(|| de::Error::missing_field("spends"))?,
+                        fee_pool: fee_pool.ok_or_else(|| de::Error::missing_field("fee_pool"))?,
+                        fee_per_word: fee_per_word
+                            .ok_or_else(|| de::Error::missing_field("fee_per_word"))?,
+                    }
+                    .into()),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct("TxBuilder", &["version", "builder"], EnvelopeVisitor)
+    }
+}
+
 impl TxBuilder {
     /// Create an empty TxBuilder
     pub fn new(fee_per_word: Nicks) -> Self {
         Self {
-            spends: BTreeMap::new(),
+            spends: BuilderSpends::default(),
             fee_pool: vec![],
             fee_per_word,
+            own_conditions: BTreeSet::new(),
         }
     }
 
@@ -339,16 +873,49 @@ impl TxBuilder {
                             .ok_or(BuildError::InvalidSpendCondition)?,
                     ))
                 })
-                .collect::<Result<BTreeMap<_, _>, _>>()?,
+                .collect::<Result<BTreeMap<_, _>, _>>()?
+                .into(),
             fee_pool: vec![],
             fee_per_word: 1 << 15,
+            own_conditions: BTreeSet::new(),
         })
     }
 
     /// Append a `SpendBuilder` to this transaction
     pub fn spend(&mut self, spend: SpendBuilder) -> Option<SpendBuilder> {
         let name = spend.note.name.clone();
-        self.spends.insert(name, spend)
+        self.spends.as_map_mut().insert(name, spend)
+    }
+
+    /// Add a note to the fee pool, to be spent only if the other spends can't cover the fee.
+    ///
+    /// Whatever is left of the note after paying the fee is refunded to `refund_lock`.
+    pub fn add_to_fee_pool(
+        &mut self,
+        note: Note,
+        spend_condition: SpendCondition,
+        refund_lock: SpendCondition,
+        include_lock_data: bool,
+    ) -> Result<&mut Self, BuildError> {
+        let name = note.name.clone();
+        if self.spends.contains_key(&name) || self.fee_pool.iter().any(|v| v.note.name == name) {
+            return Err(BuildError::DuplicateNote(name));
+        }
+        let mut spend = SpendBuilder::new(note, spend_condition, Some(refund_lock));
+        spend.compute_refund(include_lock_data);
+        self.fee_pool.push(spend);
+        Ok(self)
+    }
+
+    /// Names of the notes currently in the fee pool.
+    pub fn fee_pool_notes(&self) -> Vec<Name> {
+        self.fee_pool.iter().map(|v| v.note.name.clone()).collect()
+    }
+
+    /// Remove all notes from the fee pool. Notes already pulled into spends are not affected.
+    pub fn clear_fee_pool(&mut self) -> &mut Self {
+        self.fee_pool.clear();
+        self
     }
 
     pub fn simple_spend_base(
@@ -359,12 +926,42 @@ impl TxBuilder {
         refund_pkh: Digest,
         include_lock_data: bool,
         memo: Option<Noun>,
+    ) -> Result<&mut Self, BuildError> {
+        self.simple_spend_base_with_change(
+            notes,
+            recipient,
+            gift,
+            ChangePolicy::ToAddress(refund_pkh),
+            include_lock_data,
+            memo,
+        )
+    }
+
+    /// Same as [`TxBuilder::simple_spend_base`], with change sent according to `change`.
+    pub fn simple_spend_base_with_change(
+        &mut self,
+        notes: Vec<(Note, SpendCondition)>,
+        recipient: Digest,
+        gift: Nicks,
+        change: ChangePolicy,
+        include_lock_data: bool,
+        memo: Option<Noun>,
     ) -> Result<&mut Self, BuildError> {
         if gift == 0 {
             return Err(BuildError::ZeroGift);
         }
+        if !Self::can_afford(&notes, gift, 0) {
+            return Err(BuildError::InsufficientFunds);
+        }
 
-        let refund_lock = SpendCondition::new_pkh(Pkh::single(refund_pkh));
+        let refund_lock = match change {
+            ChangePolicy::ToAddress(pkh) => SpendCondition::new_pkh(Pkh::single(pkh)),
+            ChangePolicy::ToFirstInput => notes
+                .first()
+                .map(|(_, spend_condition)| spend_condition.clone())
+                .ok_or(BuildError::InsufficientFunds)?,
+            ChangePolicy::Burn => SpendCondition(vec![LockPrimitive::Brn]),
+        };
 
         let mut remaining_gift = gift;
 
@@ -402,6 +999,58 @@ impl TxBuilder {
         Ok(self)
     }
 
+    /// Whether `notes` hold enough assets to pay `gift` and `fee_estimate`, e.g. to check funds
+    /// before a simple spend. Nothing is mutated.
+    pub fn can_afford(notes: &[(Note, SpendCondition)], gift: Nicks, fee_estimate: Nicks) -> bool {
+        let total: u128 = notes.iter().map(|(note, _)| note.assets as u128).sum();
+        total >= gift as u128 + fee_estimate as u128
+    }
+
+    /// Output note `recipient` would receive if `gift` were paid to them with
+    /// [`TxBuilder::simple_spend`] from this builder's inputs, i.e. the notes of its spends
+    /// followed by the fee pool. The builder is not mutated.
+    pub fn preview_recipient_output(
+        &self,
+        recipient: Digest,
+        gift: Nicks,
+        include_lock_data: bool,
+    ) -> Result<Note, BuildError> {
+        let notes: Vec<(Note, SpendCondition)> = self
+            .spends
+            .values()
+            .chain(self.fee_pool.iter())
+            .map(|v| (v.note.clone(), v.spend_condition.clone()))
+            .collect();
+
+        // Burn the change, so that it can't share the recipient's lock root.
+        let mut preview = TxBuilder::new(self.fee_per_word);
+        preview.simple_spend_base_with_change(
+            notes,
+            recipient,
+            gift,
+            ChangePolicy::Burn,
+            include_lock_data,
+            None,
+        )?;
+
+        let lock_root = SpendCondition::new_pkh(Pkh::single(recipient)).hash();
+        let seeds: Vec<&Seed> = preview
+            .spends
+            .values()
+            .flat_map(|v| v.spend.seeds().0.iter())
+            .filter(|seed| seed.lock_root.hash() == lock_root)
+            .collect();
+        // Zero gifts were rejected above, so a missing output means the gift was not accounted
+        // for.
+        let name = Seed::merged_output_name(&seeds).ok_or(BuildError::AccountingMismatch)?;
+        preview
+            .build()
+            .outputs()
+            .into_iter()
+            .find(|note| note.name == name)
+            .ok_or(BuildError::AccountingMismatch)
+    }
+
     // Match CLI/hoon behavior: memo note-data must be on the *last* seed of the lock-root
     // that has the highest total gift. This is because tx engine preserves note-data from the
     // last seed for each lock-root during merge.
@@ -434,10 +1083,7 @@ impl TxBuilder {
         // that, *after memo is applied to that seed only*, it becomes the last seed in the z-set
         // iteration order for that lock-root.
         fn seed_has_memo(seed: &Seed) -> bool {
-            seed.note_data
-                .entries
-                .iter()
-                .any(|e| e.key == crate::MEMO_KEY)
+            seed.note_data.memo().is_some()
         }
 
         // Collect all seeds for this lock-root in the exact order they will be inserted into the
@@ -479,7 +1125,7 @@ impl TxBuilder {
             let (n, i, _) = seeds_for_lock.last().expect("seeds_for_lock is non-empty");
             (n.clone(), *i)
         });
-        if let Some(spend) = self.spends.get_mut(&name) {
+        if let Some(spend) = self.spends.as_map_mut().get_mut(&name) {
             if let Some(seed) = spend.spend.seeds_mut().0.get_mut(idx) {
                 seed.note_data.push_memo(memo);
             }
@@ -501,9 +1147,34 @@ impl TxBuilder {
         Ok(self)
     }
 
+    /// Same as [`TxBuilder::simple_spend`], with change sent according to `change`.
+    ///
+    /// See [`ChangePolicy`] for the privacy implications of each choice.
+    pub fn simple_spend_with_change(
+        &mut self,
+        notes: Vec<(Note, SpendCondition)>,
+        recipient: Digest,
+        gift: Nicks,
+        change: ChangePolicy,
+        include_lock_data: bool,
+        memo: Option<Noun>,
+    ) -> Result<&mut Self, BuildError> {
+        self.simple_spend_base_with_change(
+            notes,
+            recipient,
+            gift,
+            change,
+            include_lock_data,
+            memo,
+        )?
+        .recalc_and_set_fee(include_lock_data)?;
+
+        Ok(self)
+    }
+
     pub fn add_preimage(&mut self, preimage: Noun) -> Option<Digest> {
         let mut ret = None;
-        for (_, s) in self.spends.iter_mut() {
+        for (_, s) in self.spends.as_map_mut().iter_mut() {
             let r = s.add_preimage(preimage.clone());
             if r.is_some() {
                 ret = r;
@@ -513,40 +1184,175 @@ impl TxBuilder {
     }
 
     pub fn sign(&mut self, signing_key: &PrivateKey) -> &mut Self {
-        for spend in self.spends.values_mut() {
+        for spend in self.spends.as_map_mut().values_mut() {
             spend.sign(signing_key);
         }
         self
     }
 
-    pub fn validate(&mut self) -> Result<&mut Self, BuildError> {
-        let cur_fee = self.cur_fee();
-        let needed_fee = self.calc_fee();
-        if cur_fee < needed_fee {
-            return Err(BuildError::InvalidFee(needed_fee, cur_fee));
-        }
-
-        if self.spends.values().any(|v| !v.is_balanced()) {
-            return Err(BuildError::UnbalancedSpends);
+    /// Clear the signatures of all spends (including the fee pool), e.g. to re-sign after the
+    /// set of co-signers changed. Preimages are cleared too if `clear_preimages` is set.
+    pub fn reset_signatures(&mut self, clear_preimages: bool) -> &mut Self {
+        for spend in self
+            .spends
+            .as_map_mut()
+            .values_mut()
+            .chain(self.fee_pool.iter_mut())
+        {
+            spend.invalidate_sigs();
+            if clear_preimages {
+                spend.clear_preimages();
+            }
         }
+        self
+    }
 
-        let unlocks = self
-            .spends
-            .values()
-            .flat_map(|v| v.missing_unlocks())
-            .collect::<Vec<_>>();
-        if !unlocks.is_empty() {
-            return Err(BuildError::MissingUnlocks(unlocks));
+    /// Remove lock data from the refund seeds of all spends, keeping it on recipient seeds, and
+    /// recompute the (lower) fee. Signatures are invalidated.
+    pub fn strip_refund_lock_data(&mut self) -> Result<&mut Self, BuildError> {
+        for spend in self.spends.as_map_mut().values_mut() {
+            spend.strip_refund_lock_data();
         }
+        self.recalc_and_set_fee(false)
+    }
 
-        Ok(self)
+    /// Whether no spend is missing signatures. Other unlocks, such as preimages, are not checked.
+    pub fn is_fully_signed(&self) -> bool {
+        self.spends.values().all(|v| {
+            !v.missing_unlocks()
+                .iter()
+                .any(|mu| matches!(mu, MissingUnlocks::Pkh { .. }))
+        })
     }
 
-    pub fn build(&self) -> NockchainTx {
+    /// Export the spends, their sig hashes and spend conditions for co-signers.
+    pub fn export_for_signing(&self) -> SignableTx {
+        SignableTx {
+            spends: self
+                .spends
+                .iter()
+                .map(|(name, s)| SignableSpend {
+                    name: name.clone(),
+                    sig_hash: s.spend.sig_hash(),
+                    spend: s.spend.clone(),
+                    spend_condition: s.spend_condition.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Attach a signature produced from [`SignableTx::sign`] to every spend it is valid for.
+    pub fn attach_signature(
+        &mut self,
+        public_key: PublicKey,
+        signature: Signature,
+    ) -> Result<&mut Self, BuildError> {
+        let mut attached = false;
+        for spend in self.spends.as_map_mut().values_mut() {
+            attached |= spend.attach_signature(public_key.clone(), signature.clone());
+        }
+        if !attached {
+            return Err(BuildError::InvalidSignature);
+        }
+        Ok(self)
+    }
+
+    /// Merge signatures from `other`, a copy of this builder signed by other parties.
+    ///
+    /// Both builders must have the same spends, with identical seeds, fees and spend conditions.
+    pub fn merge_signatures(&mut self, other: &TxBuilder) -> Result<&mut Self, BuildError> {
+        for name in self.spends.keys().chain(other.spends.keys()) {
+            let (Some(ours), Some(theirs)) = (self.spends.get(name), other.spends.get(name)) else {
+                return Err(BuildError::SpendMismatch(name.clone()));
+            };
+            if ours.spend.sig_hash() != theirs.spend.sig_hash()
+                || ours.spend_condition.hash() != theirs.spend_condition.hash()
+            {
+                return Err(BuildError::SpendMismatch(name.clone()));
+            }
+        }
+
+        for (name, theirs) in other.spends.iter() {
+            let ours = self
+                .spends
+                .as_map_mut()
+                .get_mut(name)
+                .expect("checked above");
+            for (public_key, signature) in theirs.spend.signatures() {
+                ours.attach_signature(public_key, signature);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Merge `other`, an independently built transaction spending different notes, into this one,
+    /// e.g. to batch payments. Nothing is merged if this fails.
+    ///
+    /// Both builders must use the same fee rate. Fees are not rebalanced, so follow with
+    /// [`TxBuilder::recalc_and_set_fee`].
+    pub fn merge(&mut self, other: TxBuilder) -> Result<&mut Self, BuildError> {
+        if self.fee_per_word != other.fee_per_word {
+            return Err(BuildError::FeeRateMismatch(
+                self.fee_per_word,
+                other.fee_per_word,
+            ));
+        }
+        let mut names: BTreeSet<&Name> = self
+            .spends
+            .keys()
+            .chain(self.fee_pool.iter().map(|v| &v.note.name))
+            .collect();
+        for name in other
+            .spends
+            .keys()
+            .chain(other.fee_pool.iter().map(|v| &v.note.name))
+        {
+            if !names.insert(name) {
+                return Err(BuildError::DuplicateNote(name.clone()));
+            }
+        }
+
+        self.spends.as_map_mut().extend(other.spends.into_inner());
+        self.fee_pool.extend(other.fee_pool);
+        self.own_conditions.extend(other.own_conditions);
+        Ok(self)
+    }
+
+    /// Set the conditions the wallet can spend, so that [`TxBuilder::lints`] flags refunds sent
+    /// anywhere else. Not checked if empty, and not serialized with the builder.
+    pub fn set_own_conditions(&mut self, conditions: Vec<SpendCondition>) -> &mut Self {
+        self.own_conditions = conditions.iter().map(|c| c.hash()).collect();
+        self
+    }
+
+    pub fn validate(&mut self) -> Result<&mut Self, BuildError> {
+        let cur_fee = self.cur_fee();
+        let needed_fee = self.calc_fee();
+        if cur_fee < needed_fee {
+            return Err(BuildError::InvalidFee(needed_fee, cur_fee));
+        }
+
+        if self.spends.values().any(|v| !v.is_balanced()) {
+            return Err(BuildError::UnbalancedSpends);
+        }
+
+        let unlocks = self
+            .spends
+            .values()
+            .flat_map(|v| v.missing_unlocks())
+            .collect::<Vec<_>>();
+        if !unlocks.is_empty() {
+            return Err(BuildError::MissingUnlocks(unlocks));
+        }
+
+        Ok(self)
+    }
+
+    pub fn build(&self) -> NockchainTx {
         let mut display = TransactionDisplay::default();
         let mut spends = Spends(Vec::new());
 
-        for (name, spend) in &self.spends {
+        for (name, spend) in self.spends.iter() {
             display
                 .inputs
                 .insert(name.clone(), spend.spend_condition.clone());
@@ -567,13 +1373,7 @@ impl TxBuilder {
         let id = (&version, &spends).hash();
         let (spends, witness_data) = spends.split_witness();
 
-        NockchainTx {
-            version,
-            id,
-            spends,
-            display,
-            witness_data,
-        }
+        NockchainTx::new(version, id, spends, display, witness_data)
     }
 
     pub fn all_notes(&self) -> BTreeMap<Name, (Note, SpendCondition)> {
@@ -591,14 +1391,200 @@ impl TxBuilder {
         self.spends.values().map(|v| v.spend.fee()).sum::<Nicks>()
     }
 
-    pub fn calc_fee(&self) -> Nicks {
-        let mut fee = 0;
+    /// Sum of all refund (change) gifts across the builder's spends.
+    pub fn total_change(&self) -> Nicks {
+        self.spends
+            .values()
+            .filter_map(|v| v.cur_refund())
+            .map(|v| v.gift)
+            .sum::<Nicks>()
+    }
+
+    /// List refunds that are smaller than `threshold`, so that the caller can warn about dust.
+    pub fn dust_refunds(&self, threshold: Nicks) -> Vec<(Name, Nicks)> {
+        self.spends
+            .iter()
+            .filter_map(|(name, v)| Some((name.clone(), v.cur_refund()?.gift)))
+            .filter(|(_, gift)| *gift < threshold)
+            .collect()
+    }
+
+    /// Per-input components of [`TxBuilder::calc_fee`].
+    pub fn fee_breakdown(&self) -> Vec<InputFee> {
+        self.spends
+            .values()
+            .map(|v| v.fee_breakdown(self.fee_per_word))
+            .collect()
+    }
+
+    /// Project the outcome of building this transaction, without validating it.
+    pub fn dry_run(&self) -> DryRun {
+        DryRun {
+            outputs: self.build().outputs(),
+            fee: self.calc_fee(),
+            cur_fee: self.cur_fee(),
+            missing_unlocks: self
+                .spends
+                .values()
+                .flat_map(|v| v.missing_unlocks())
+                .collect(),
+            balanced: self.spends.values().all(|v| v.is_balanced()),
+        }
+    }
+
+    /// Full report of the transaction being built, for display.
+    pub fn describe(&self) -> TxReport {
+        let inputs = self
+            .spends
+            .values()
+            .map(|v| InputReport {
+                name: v.note.name.clone(),
+                assets: v.note.assets,
+                lock: v.spend_condition.describe(),
+            })
+            .collect();
+
+        let locks: BTreeMap<Digest, &LockRoot> = self
+            .spends
+            .values()
+            .flat_map(|v| v.spend.seeds().0.iter())
+            .map(|seed| ((true, seed.lock_root.hash()).hash(), &seed.lock_root))
+            .collect();
+        let outputs = self
+            .build()
+            .outputs()
+            .into_iter()
+            .map(|note| {
+                let lock_root = locks.get(&note.name.first);
+                OutputReport {
+                    lock_root: lock_root.map(|v| v.hash()).unwrap_or(note.name.first),
+                    lock: match lock_root {
+                        Some(LockRoot::Lock(lock)) => Some(lock.describe()),
+                        _ => None,
+                    },
+                    gift: note.assets,
+                    has_memo: note.note_data.memo().is_some(),
+                    has_lock_data: note.note_data.has_lock(),
+                    name: note.name,
+                }
+            })
+            .collect();
+
+        TxReport {
+            inputs,
+            outputs,
+            fee: self.cur_fee(),
+            needed_fee: self.calc_fee(),
+            balanced: self.spends.values().all(|v| v.is_balanced()),
+            missing_unlocks: self
+                .spends
+                .iter()
+                .map(|(name, v)| (name.clone(), v.missing_unlocks()))
+                .filter(|(_, v)| !v.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Warnings about likely mistakes in the transaction being built.
+    pub fn lints(&self) -> Vec<TxLint> {
+        let mut lints = Vec::new();
+
+        // Whether any seed of each complex output lock carries the lock data.
+        let mut complex_outputs: BTreeMap<Digest, bool> = BTreeMap::new();
+        for seed in self.spends.values().flat_map(|v| v.spend.seeds().0.iter()) {
+            let LockRoot::Lock(lock) = &seed.lock_root else {
+                continue;
+            };
+            if lock.is_single_pkh().is_some() {
+                continue;
+            }
+            let has_lock_data = seed.note_data.has_lock();
+            *complex_outputs.entry(lock.hash()).or_default() |= has_lock_data;
+        }
+        for (lock_hash, has_lock_data) in complex_outputs {
+            if !has_lock_data {
+                lints.push(TxLint::MissingLockDataForComplexOutput { lock_hash });
+            }
+        }
+
+        if !self.own_conditions.is_empty() {
+            for (name, spend) in self.spends.iter() {
+                let Some(refund_lock) = &spend.refund_lock else {
+                    continue;
+                };
+                let lock_hash = refund_lock.hash();
+                if !self.own_conditions.contains(&lock_hash) {
+                    lints.push(TxLint::RefundNotSelfSpendable {
+                        name: name.clone(),
+                        lock_hash,
+                    });
+                }
+            }
+        }
+
+        if self.fee_per_word == 0 {
+            lints.push(TxLint::ZeroFeeRate);
+        }
+        let fee = self.cur_fee();
+        let inputs_value: Nicks = self.spends.values().map(|v| v.note.assets).sum();
+        if fee as u128 * 10 > inputs_value as u128 {
+            lints.push(TxLint::ExcessiveFee { fee, inputs_value });
+        }
 
-        for s in self.spends.values() {
-            fee += s.unclamped_fee(self.fee_per_word);
+        lints
+    }
+
+    /// Index of the change output in `self.build().outputs()`, i.e. the one paying the refund lock.
+    ///
+    /// Output order is content-addressed (see [`RawTx::outputs`]), so it cannot be shuffled to
+    /// hide the change. Returns `None` if there is no change, or if spends refund to different
+    /// locks.
+    pub fn change_output_index(&self) -> Option<usize> {
+        let mut refund_locks = self
+            .spends
+            .values()
+            .filter(|v| v.cur_refund().is_some())
+            .filter_map(|v| v.refund_lock.as_ref())
+            .map(|lock| lock.first_name());
+        let first = refund_locks.next()?;
+        if refund_locks.any(|v| v != first) {
+            return None;
         }
+        self.build()
+            .outputs()
+            .iter()
+            .position(|note| note.name.first == first)
+    }
+
+    pub fn calc_fee(&self) -> Nicks {
+        *self.spends.fee.get_or_init(|| {
+            let mut fee = 0;
 
-        fee.max(Spend::MIN_FEE)
+            for s in self.spends.values() {
+                fee += s.unclamped_fee(self.fee_per_word);
+            }
+
+            fee.max(Spend::MIN_FEE)
+        })
+    }
+
+    /// Attach a custom note-data entry to a seed of the spend of `name`, then recompute the fee.
+    ///
+    /// See [`SpendBuilder::set_seed_note_data`].
+    pub fn set_seed_note_data(
+        &mut self,
+        name: &Name,
+        seed_index: usize,
+        key: String,
+        val: Noun,
+        include_lock_data: bool,
+    ) -> Result<&mut Self, BuildError> {
+        self.spends
+            .as_map_mut()
+            .get_mut(name)
+            .ok_or_else(|| BuildError::NoteNotFound(name.clone()))?
+            .set_seed_note_data(seed_index, key, val)?;
+        self.recalc_and_set_fee(include_lock_data)
     }
 
     pub fn recalc_and_set_fee(&mut self, include_lock_data: bool) -> Result<&mut Self, BuildError> {
@@ -614,7 +1600,7 @@ impl TxBuilder {
     ) -> Result<&mut Self, BuildError> {
         let cur_fee = self.cur_fee();
 
-        let mut spends = self.spends.values_mut().collect::<Vec<_>>();
+        let mut spends = self.spends.as_map_mut().values_mut().collect::<Vec<_>>();
 
         if cur_fee == fee {
             Ok(self)
@@ -684,6 +1670,8 @@ impl TxBuilder {
             }
         } else {
             let mut refund_left = cur_fee - fee;
+            // Fee still required, once notes are returned to the fee pool
+            let mut target_fee = fee;
 
             // Sort by smallest fee, so that we can return as many low-fee notes to fee pool as
             // possible.
@@ -710,7 +1698,10 @@ impl TxBuilder {
 
             let mut return_to_pool = vec![];
 
-            for s in spends {
+            for i in 0..spends.len() {
+                // Whether a later spend can take back fee freed by this one
+                let later_refundable = spends[i + 1..].iter().any(|v| v.refund_lock.is_some());
+                let s = &mut spends[i];
                 if s.refund_lock.is_some() {
                     let add_refund = s.spend.fee().min(refund_left);
 
@@ -721,21 +1712,27 @@ impl TxBuilder {
                         s.compute_refund(include_lock_data);
                     }
 
-                    if s.spend.fee() == add_refund {
+                    let refund_only = s.spend.seeds().0.len() == 1 && s.cur_refund().is_some();
+                    if s.spend.fee() == 0 && refund_only {
                         return_to_pool.push(s.note.name.clone());
                         // We are returning this note to pool (making it unused), all its required
-                        // fee shall disappear. The only case we don't handle here is whenever we
-                        // reach the MIN_FEE (256 nicks). Hence, TODO: handle MIN_FEE case. This is
-                        // irrelevant for the current consensus version with high fees.
-                        refund_left =
-                            refund_left.saturating_sub(s.unclamped_fee(self.fee_per_word));
+                        // fee shall disappear, and can be refunded by the remaining spends. That
+                        // is, unless the total fee would drop below MIN_FEE, or no remaining
+                        // spend has a refund, in which case it is left as overpayment.
+                        if adjust_fee && later_refundable {
+                            let freed = s
+                                .unclamped_fee(self.fee_per_word)
+                                .min(target_fee.saturating_sub(Spend::MIN_FEE));
+                            target_fee -= freed;
+                            refund_left += freed;
+                        }
                     }
                 }
             }
 
             // Take all notes that we are meant to return to fee pool, and return there.
             for note in return_to_pool {
-                let sp = self.spends.remove(&note).unwrap();
+                let sp = self.spends.as_map_mut().remove(&note).unwrap();
                 self.fee_pool.push(sp);
             }
 
@@ -754,11 +1751,16 @@ pub enum BuildError {
     InsufficientFunds,
     AccountingMismatch,
     NoteNotFound(Name),
+    DuplicateNote(Name),
+    SeedNotFound(usize),
     InvalidFee(Nicks, Nicks),
     InvalidVersion,
     InvalidSpendCondition,
+    InvalidSignature,
+    SpendMismatch(Name),
     UnbalancedSpends,
     MissingUnlocks(Vec<MissingUnlocks>),
+    FeeRateMismatch(Nicks, Nicks),
 }
 
 impl core::fmt::Display for BuildError {
@@ -772,6 +1774,10 @@ impl core::fmt::Display for BuildError {
             BuildError::NoteNotFound(name) => {
                 write!(f, "Unable to find note [{} {}]", name.first, name.last)
             }
+            BuildError::DuplicateNote(name) => {
+                write!(f, "Note [{} {}] is already used", name.first, name.last)
+            }
+            BuildError::SeedNotFound(index) => write!(f, "No seed at index {index}"),
             BuildError::InvalidFee(expected, got) => {
                 write!(
                     f,
@@ -782,6 +1788,19 @@ impl core::fmt::Display for BuildError {
             BuildError::InvalidSpendCondition => {
                 write!(f, "Spend condition is invalid (mismatch?)")
             }
+            BuildError::InvalidSignature => {
+                write!(f, "Signature is not valid for any spend")
+            }
+            BuildError::SpendMismatch(name) => {
+                write!(
+                    f,
+                    "Spend of note [{} {}] differs between builders",
+                    name.first, name.last
+                )
+            }
+            BuildError::FeeRateMismatch(ours, theirs) => {
+                write!(f, "Builders use different fee rates ({ours} and {theirs})")
+            }
             BuildError::UnbalancedSpends => write!(
                 f,
                 "Some spends are not balanced (forgot to compute refunds?)"
@@ -815,6 +1834,59 @@ mod tests {
         (ek.private_key.unwrap(), ek.public_key)
     }
 
+    /// Note of `assets` nicks locked to `spend_condition`, with `last` as the last name.
+    fn fixture_note(spend_condition: &SpendCondition, last: Digest, assets: Nicks) -> Note {
+        Note {
+            version: Version::V1,
+            origin_page: 13,
+            name: Name::new(spend_condition.first_name(), last),
+            note_data: NoteData::empty(),
+            assets,
+        }
+    }
+
+    /// A [`fixture_note`] of 4294967296 nicks locked to the single pkh of [`keys`], with its
+    /// spend condition, and a recipient.
+    fn spend_fixture() -> (PrivateKey, SpendCondition, Note, Digest) {
+        let (private_key, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let note = fixture_note(
+            &spend_condition,
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            4294967296,
+        );
+        let recipient = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+        (private_key, spend_condition, note, recipient)
+    }
+
+    #[test]
+    fn test_spend_builder_serde_stability() {
+        // Persisted wallet state relies on this, so changes must be deliberate.
+        let lock = SpendCondition::new_pkh(Pkh::single(
+            "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                .try_into()
+                .unwrap(),
+        ));
+        let note = fixture_note(&lock, lock.hash(), 4294967296);
+        let mut spend = SpendBuilder::new(note, lock.clone(), Some(lock.clone()));
+        let recipient = SpendCondition::new_pkh(Pkh::single(
+            "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                .try_into()
+                .unwrap(),
+        ));
+        let seed = spend.build_seed(recipient, 1234567, false);
+        spend.seed(seed).fee(1000).compute_refund(false);
+
+        let json = r#"{"note":{"version":"V1","origin_page":13,"name":{"first":[15660178877709197696,17259916204275481612,14163934173677759513,8076964737523780252,3975647044641071116],"last":[8229386063874774508,16152222262208212050,2096635905691427881,16643284189923061907,6662142103960148334],"_sig":0},"note_data":{"entries":[]},"assets":4294967296},"spend":["1",[[[[["686b70",["1",[["66d2e43d96d0c909",["29f7aa9b48daa83a",["fcb6b95379982a4c",["134054db284f6c69","753d78986c69ea76"]]]],["0","0"]]]],"0"],["1",[["7234a70620ce55ec",["e0283891c013ac52",["1d18bf3f6e112429",["e6f8d2ca743d3093","5c74aef22db7d96e"]]]],"0"]]],["0",["0","0"]]],[[["0",[["9afce2cc17ee7dcf",["847f1322a4bd114c",["1a2ae272b49b97c7",["4a65bacc38d2ecd5","ef59a91a1b9a8af3"]]]],["0",["12d687",["7fa0caf0621e5fb9",["2da9c66b962260f5",["750f1e92e31aae",["1dd5db74f233c6b2","64ca72adb3b3d5d3"]]]]]]]],["0",[["0",[["7234a70620ce55ec",["e0283891c013ac52",["1d18bf3f6e112429",["e6f8d2ca743d3093","5c74aef22db7d96e"]]]],["0",["ffed2591",["7fa0caf0621e5fb9",["2da9c66b962260f5",["750f1e92e31aae",["1dd5db74f233c6b2","64ca72adb3b3d5d3"]]]]]]]],["0","0"]]]],"3e8"]]],"spend_condition":[["686b70",["1",[["66d2e43d96d0c909",["29f7aa9b48daa83a",["fcb6b95379982a4c",["134054db284f6c69","753d78986c69ea76"]]]],["0","0"]]]],"0"],"refund_lock":["0",[["686b70",["1",[["66d2e43d96d0c909",["29f7aa9b48daa83a",["fcb6b95379982a4c",["134054db284f6c69","753d78986c69ea76"]]]],["0","0"]]]],"0"]]}"#;
+        assert_eq!(serde_json::to_string(&spend).unwrap(), json);
+        let decoded: SpendBuilder = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+
     #[test]
     fn test_from_spend_rejects_note_version_spend_variant_mismatch() {
         let (private_key, _) = keys();
@@ -902,65 +1974,900 @@ mod tests {
             LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
             LockPrimitive::Tim(LockTim::coinbase()),
         ]);
-        let tx = TxBuilder::new(1)
-            .simple_spend_base(
-                vec![(note.clone(), spend_condition.clone())],
-                recipient,
-                gift,
-                refund_pkh,
-                true,
-                None,
-            )
-            .unwrap()
-            .set_fee_and_balance_refund(fee, false, true)
-            .unwrap()
-            .sign(&private_key)
-            .validate()
-            .unwrap()
-            .build();
+        let tx = TxBuilder::new(1)
+            .simple_spend_base(
+                vec![(note.clone(), spend_condition.clone())],
+                recipient,
+                gift,
+                refund_pkh,
+                true,
+                None,
+            )
+            .unwrap()
+            .set_fee_and_balance_refund(fee, false, true)
+            .unwrap()
+            .sign(&private_key)
+            .validate()
+            .unwrap()
+            .build();
+
+        assert_eq!(
+            tx.id.to_string(),
+            "3pmkA1knKhJzmd28t5TULP9DADK7GhWsHaNSTpPcGcN4nxzrWsDK2xe",
+        );
+        assert_eq!(tx.total_fee(), fee);
+
+        let mut tx = TxBuilder::new(1 << 17);
+
+        tx.simple_spend_base(
+            vec![(note.clone(), spend_condition.clone())],
+            recipient,
+            gift,
+            refund_pkh,
+            true,
+            None,
+        )
+        .unwrap()
+        .set_fee_and_balance_refund(fee, false, true)
+        .unwrap()
+        .sign(&private_key);
+
+        assert!(tx.validate().is_err());
+
+        let fee_per_word = 40000;
+        let mut builder = TxBuilder::new(fee_per_word);
+
+        builder
+            .simple_spend(
+                vec![(note, spend_condition)],
+                recipient,
+                gift,
+                refund_pkh,
+                false,
+                None,
+            )
+            .unwrap();
+
+        let fee1 = builder.calc_fee();
+
+        let tx = builder.sign(&private_key).build();
+
+        assert_eq!(tx.to_raw_tx().spends.fee(fee_per_word), 2520000);
+        assert_eq!(fee1, 2520000);
+        assert_eq!(tx.total_fee(), 2520000);
+    }
+
+    #[test]
+    fn test_fee_breakdown_and_dry_run() {
+        let (private_key, _) = keys();
+
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let note = fixture_note(&spend_condition, 1u64.hash(), 4294967296);
+
+        let mut builder = TxBuilder::new(40000);
+        builder
+            .simple_spend(
+                vec![(note, spend_condition)],
+                "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                    .try_into()
+                    .unwrap(),
+                1234567,
+                "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                    .try_into()
+                    .unwrap(),
+                false,
+                None,
+            )
+            .unwrap();
+
+        let breakdown = builder.fee_breakdown();
+        assert_eq!(breakdown.len(), 1);
+        assert_eq!(breakdown[0].unlock_words, 35);
+        assert_eq!(breakdown[0].fee, builder.calc_fee());
+
+        let dry_run = builder.dry_run();
+        assert_eq!(dry_run.fee, builder.calc_fee());
+        assert_eq!(dry_run.cur_fee, builder.cur_fee());
+        assert!(dry_run.balanced);
+        assert_eq!(dry_run.missing_unlocks.len(), 1);
+        assert_eq!(dry_run.outputs.len(), 2);
+
+        builder.sign(&private_key);
+        let breakdown = builder.fee_breakdown();
+        assert_eq!(breakdown[0].unlock_words, 0);
+        assert!(builder.dry_run().missing_unlocks.is_empty());
+    }
+
+    #[test]
+    fn test_set_seed_note_data() {
+        let (private_key, _) = keys();
+
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let note = fixture_note(&spend_condition, 1u64.hash(), 4294967296);
+        let recipient: Digest = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+
+        let mut builder = TxBuilder::new(40000);
+        builder
+            .simple_spend(
+                vec![(note.clone(), spend_condition)],
+                recipient,
+                1234567,
+                "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                    .try_into()
+                    .unwrap(),
+                false,
+                None,
+            )
+            .unwrap()
+            .sign(&private_key);
+        let fee_before = builder.cur_fee();
+
+        let recipient_root = SpendCondition::new_pkh(Pkh::single(recipient)).hash();
+        let seed_index = builder.all_spends()[&note.name]
+            .spend
+            .seeds()
+            .0
+            .iter()
+            .position(|s| s.lock_root.hash() == recipient_root)
+            .unwrap();
+
+        assert!(matches!(
+            builder.set_seed_note_data(&note.name, 99, "app".to_string(), 1.to_noun(), false),
+            Err(BuildError::SeedNotFound(99))
+        ));
+
+        let val = ("order", 42).to_noun();
+        builder
+            .set_seed_note_data(
+                &note.name,
+                seed_index,
+                "app".to_string(),
+                val.clone(),
+                false,
+            )
+            .unwrap();
+
+        assert!(builder.cur_fee() > fee_before);
+        assert_eq!(builder.cur_fee(), builder.calc_fee());
+        assert!(builder.all_spends()[&note.name].is_balanced());
+        assert!(!builder.all_spends()[&note.name]
+            .missing_unlocks()
+            .is_empty());
+
+        let tx = builder.sign(&private_key).validate().unwrap().build();
+        let output = tx
+            .outputs()
+            .into_iter()
+            .find(|n| n.name.first == (true, recipient_root).hash())
+            .unwrap();
+        let entry = output
+            .note_data
+            .entries
+            .iter()
+            .find(|e| e.key == "app")
+            .unwrap();
+        assert_eq!(entry.val.hash(), val.hash());
+    }
+
+    #[test]
+    fn test_fee_pool_management() {
+        let (private_key, _) = keys();
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let main = fixture_note(
+            &spend_condition,
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            3000,
+        );
+        let extra = fixture_note(
+            &spend_condition,
+            "6yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            3000,
+        );
+        let refund_pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+        let refund_lock = SpendCondition::new_pkh(Pkh::single(refund_pkh));
+
+        let new_builder = || {
+            let mut builder = TxBuilder::new(8);
+            builder
+                .simple_spend_base(
+                    vec![(main.clone(), spend_condition.clone())],
+                    "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                        .try_into()
+                        .unwrap(),
+                    2900,
+                    refund_pkh,
+                    false,
+                    None,
+                )
+                .unwrap();
+            builder
+        };
+
+        // The 100 nick refund of the main input can't cover the fee on its own.
+        let mut builder = new_builder();
+        assert!(builder.fee_pool_notes().is_empty());
+        assert!(matches!(
+            builder.recalc_and_set_fee(false),
+            Err(BuildError::InsufficientFunds)
+        ));
+
+        let mut builder = new_builder();
+        builder
+            .add_to_fee_pool(
+                extra.clone(),
+                spend_condition.clone(),
+                refund_lock.clone(),
+                false,
+            )
+            .unwrap();
+        assert_eq!(builder.fee_pool_notes(), vec![extra.name.clone()]);
+        assert!(matches!(
+            builder.add_to_fee_pool(
+                main.clone(),
+                spend_condition.clone(),
+                refund_lock.clone(),
+                false
+            ),
+            Err(BuildError::DuplicateNote(_))
+        ));
+
+        builder.recalc_and_set_fee(false).unwrap();
+        assert!(builder.fee_pool_notes().is_empty());
+        assert!(builder.all_spends().contains_key(&extra.name));
+        assert_eq!(builder.cur_fee(), builder.calc_fee());
+        builder.sign(&private_key).validate().unwrap();
+
+        let mut builder = new_builder();
+        builder
+            .add_to_fee_pool(extra, spend_condition, refund_lock, false)
+            .unwrap()
+            .clear_fee_pool();
+        assert!(builder.fee_pool_notes().is_empty());
+        assert!(builder.recalc_and_set_fee(false).is_err());
+    }
+
+    #[test]
+    fn test_export_for_signing() {
+        let (coordinator, _) = keys();
+        let mnemonic = Mnemonic::parse("dice domain inspire horse time initial monitor nature mass impose tone benefit vibrant dash kiss mosquito rice then color ribbon agent method drop fat").unwrap();
+        let cosigner = derive_master_key(&mnemonic.to_seed(""))
+            .derive_child(1)
+            .private_key
+            .unwrap();
+        let spend_condition = SpendCondition(vec![LockPrimitive::Pkh(Pkh::new(
+            2,
+            vec![
+                coordinator.public_key().hash(),
+                cosigner.public_key().hash(),
+            ],
+        ))]);
+        let main = fixture_note(
+            &spend_condition,
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            4294967296,
+        );
+        let pooled = fixture_note(
+            &spend_condition,
+            "6yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            4294967296,
+        );
+        let refund_pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+
+        let mut builder = TxBuilder::new(1);
+        builder
+            .simple_spend_base(
+                vec![(main.clone(), spend_condition.clone())],
+                "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                    .try_into()
+                    .unwrap(),
+                1234567,
+                refund_pkh,
+                false,
+                None,
+            )
+            .unwrap()
+            .add_to_fee_pool(
+                pooled,
+                spend_condition.clone(),
+                SpendCondition::new_pkh(Pkh::single(refund_pkh)),
+                false,
+            )
+            .unwrap()
+            .recalc_and_set_fee(false)
+            .unwrap();
+        builder.sign(&coordinator);
+
+        // Only the spend goes out, not the fee pool.
+        let signable = builder.export_for_signing();
+        assert_eq!(signable.spends.len(), 1);
+        assert_eq!(signable.spends[0].name, main.name);
+        assert_eq!(
+            signable.spends[0].sig_hash,
+            builder.all_spends()[&main.name].spend.sig_hash()
+        );
+
+        let sigs = signable.sign(&cosigner);
+        assert_eq!(sigs.len(), 1);
+        let (public_key, signature) = sigs[0].clone();
+
+        // A signature over the wrong hash, or by a key not in the lock, is rejected.
+        let bogus = cosigner.sign(&refund_pkh);
+        assert!(matches!(
+            builder.attach_signature(public_key.clone(), bogus),
+            Err(BuildError::InvalidSignature)
+        ));
+        assert!(matches!(
+            builder.attach_signature(derive_master_key(&[0; 64]).public_key, signature.clone()),
+            Err(BuildError::InvalidSignature)
+        ));
+        assert!(builder.validate().is_err());
+
+        builder
+            .attach_signature(public_key.clone(), signature.clone())
+            .unwrap();
+        builder.validate().unwrap();
+        // Attaching the same signature again doesn't duplicate it.
+        assert!(builder.attach_signature(public_key, signature).is_err());
+        builder.build().to_raw_tx().verify_signatures().unwrap();
+    }
+
+    #[test]
+    fn test_try_fee() {
+        let (_, spend_condition, note, _) = spend_fixture();
+        let mut spend = SpendBuilder::new(note, spend_condition.clone(), Some(spend_condition));
+        let seed = spend.build_seed(
+            SpendCondition::new_pkh(Pkh::single(2u64.hash())),
+            4294967296 - 1000,
+            false,
+        );
+        spend.seed(seed);
+        spend.compute_refund(false);
+        assert!(spend.is_balanced());
+
+        assert!(matches!(
+            spend.try_fee(1001),
+            Err(BuildError::InsufficientFunds)
+        ));
+        assert_eq!(spend.spend.fee(), 0);
+        assert!(spend.is_balanced());
+
+        // The refund gives way to the fee.
+        spend.try_fee(1000).unwrap().compute_refund(false);
+        assert_eq!(spend.spend.fee(), 1000);
+        assert!(spend.cur_refund().is_none());
+        assert!(spend.is_balanced());
+    }
+
+    #[test]
+    fn test_down_balancing_without_refundable_spend() {
+        let (private_key, _) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(private_key.public_key().hash()));
+        let main = fixture_note(
+            &spend_condition,
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            4294967296,
+        );
+        let extra = fixture_note(
+            &spend_condition,
+            "6yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            4294967296,
+        );
+        let recipient: Digest = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+        // The main spend has no refund, so it can't take back any fee.
+        let main_spend = |fee| {
+            let mut spend = SpendBuilder::new(main.clone(), spend_condition.clone(), None);
+            let seed = spend.build_seed(
+                SpendCondition::new_pkh(Pkh::single(recipient)),
+                main.assets - fee,
+                false,
+            );
+            spend.seed(seed).fee(fee);
+            spend
+        };
+        let with_extra = |fee| {
+            let mut builder = TxBuilder::new(1 << 15);
+            builder.spend(main_spend(fee));
+            builder
+                .add_to_fee_pool(
+                    extra.clone(),
+                    spend_condition.clone(),
+                    spend_condition.clone(),
+                    false,
+                )
+                .unwrap()
+                // Overpay, so that the fee pool note gets pulled in.
+                .set_fee_and_balance_refund(fee + 1_000_000, false, false)
+                .unwrap();
+            assert!(builder.all_spends().contains_key(&extra.name));
+            builder
+        };
+        let required = with_extra(0).calc_fee();
+
+        // Down-balancing returns the extra note to the pool. The fee it frees can't be refunded
+        // by the main spend, and is left as overpayment.
+        let mut builder = with_extra(required);
+        builder.recalc_and_set_fee(true).unwrap();
+        assert_eq!(builder.fee_pool_notes(), vec![extra.name]);
+        assert_eq!(builder.cur_fee(), required);
+        assert!(builder.calc_fee() < required);
+        builder.sign(&private_key).validate().unwrap();
+    }
+
+    #[test]
+    fn test_down_balancing_keeps_min_fee() {
+        let (private_key, _) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(private_key.public_key().hash()));
+        let main = fixture_note(
+            &spend_condition,
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            5000,
+        );
+        let extra = fixture_note(
+            &spend_condition,
+            "6yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+            3000,
+        );
+        let refund_pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+
+        // With 1 nick per word, the fee is well below MIN_FEE.
+        let mut builder = TxBuilder::new(1);
+        builder
+            .simple_spend_base(
+                vec![(main.clone(), spend_condition.clone())],
+                "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                    .try_into()
+                    .unwrap(),
+                2900,
+                refund_pkh,
+                false,
+                None,
+            )
+            .unwrap()
+            .add_to_fee_pool(
+                extra.clone(),
+                spend_condition,
+                SpendCondition::new_pkh(Pkh::single(refund_pkh)),
+                false,
+            )
+            .unwrap();
+        assert_eq!(builder.calc_fee(), Spend::MIN_FEE);
+
+        // Overpay, so that the fee pool note gets pulled in.
+        builder
+            .set_fee_and_balance_refund(2300, false, false)
+            .unwrap();
+        assert!(builder.all_spends().contains_key(&extra.name));
+
+        // Down-balancing returns the extra note to the pool, but its freed fee must not take the
+        // total below MIN_FEE.
+        builder.recalc_and_set_fee(false).unwrap();
+        assert_eq!(builder.fee_pool_notes(), vec![extra.name]);
+        assert_eq!(builder.cur_fee(), Spend::MIN_FEE);
+        builder.sign(&private_key).validate().unwrap();
+    }
+
+    #[test]
+    fn test_simple_spend_with_change() {
+        let (private_key, _) = keys();
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let note = fixture_note(&spend_condition, 1u64.hash(), 4294967296);
+        let recipient: Digest = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+        let change_pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+
+        let change_lock = |change: ChangePolicy| {
+            let mut builder = TxBuilder::new(1 << 15);
+            builder
+                .simple_spend_with_change(
+                    vec![(note.clone(), spend_condition.clone())],
+                    recipient,
+                    1234567,
+                    change,
+                    false,
+                    None,
+                )
+                .unwrap();
+            builder.sign(&private_key).validate().unwrap();
+            let spend = &builder.all_spends()[&note.name];
+            assert_eq!(spend.spend.seeds().0.len(), 2);
+            spend.cur_refund().unwrap().lock_root.hash()
+        };
+
+        assert_eq!(
+            change_lock(ChangePolicy::ToAddress(change_pkh)),
+            SpendCondition::new_pkh(Pkh::single(change_pkh)).hash()
+        );
+        assert_eq!(
+            change_lock(ChangePolicy::ToFirstInput),
+            spend_condition.hash()
+        );
+        assert_eq!(
+            change_lock(ChangePolicy::Burn),
+            SpendCondition(vec![LockPrimitive::Brn]).hash()
+        );
+
+        // `ToAddress` is what `simple_spend` does.
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(
+                vec![(note.clone(), spend_condition.clone())],
+                recipient,
+                1234567,
+                change_pkh,
+                false,
+                None,
+            )
+            .unwrap();
+        let mut with_change = TxBuilder::new(1 << 15);
+        with_change
+            .simple_spend_with_change(
+                vec![(note, spend_condition)],
+                recipient,
+                1234567,
+                ChangePolicy::ToAddress(change_pkh),
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(builder.build().id, with_change.build().id);
+    }
+
+    #[test]
+    fn test_tx_builder_serde_version() {
+        use ciborium::Value;
+
+        let (_, spend_condition, note, _) = spend_fixture();
+        let mut builder = TxBuilder::new(1);
+        builder
+            .simple_spend(
+                vec![(note, spend_condition.clone())],
+                spend_condition.hash(),
+                1234567,
+                spend_condition.hash(),
+                false,
+                None,
+            )
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&builder, &mut bytes).unwrap();
+        let loaded: TxBuilder = ciborium::from_reader(&bytes[..]).unwrap();
+        assert_eq!(loaded.build().id, builder.build().id);
+
+        let mut value: Value = ciborium::from_reader(&bytes[..]).unwrap();
+        let Value::Map(entries) = &mut value else {
+            panic!("expected a map");
+        };
+        assert_eq!(entries[0], (Value::from("version"), Value::from(1)));
+        entries[0].1 = Value::from(TX_BUILDER_VERSION + 1);
+        let mut bumped = Vec::new();
+        ciborium::into_writer(&value, &mut bumped).unwrap();
+        let err = ciborium::from_reader::<TxBuilder, _>(&bumped[..])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unsupported TxBuilder version 2"));
+
+        // Sorted keys put `builder` before `version`
+        let Value::Map(entries) = &mut value else {
+            unreachable!()
+        };
+        entries[0].1 = Value::from(TX_BUILDER_VERSION);
+        entries.reverse();
+        let mut reversed = Vec::new();
+        ciborium::into_writer(&value, &mut reversed).unwrap();
+        let loaded: TxBuilder = ciborium::from_reader(&reversed[..]).unwrap();
+        assert_eq!(loaded.build().id, builder.build().id);
+
+        // Builders saved before versioning are the bare builder map
+        let Value::Map(entries) = &mut value else {
+            unreachable!()
+        };
+        let (key, legacy) = entries.remove(0);
+        assert_eq!(key, Value::from("builder"));
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&legacy, &mut bytes).unwrap();
+        let loaded: TxBuilder = ciborium::from_reader(&bytes[..]).unwrap();
+        assert_eq!(loaded.build().id, builder.build().id);
+
+        // A builder without its version is not mistaken for a legacy one
+        let Value::Map(entries) = &mut value else {
+            unreachable!()
+        };
+        entries[0] = (Value::from("builder"), legacy);
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&value, &mut bytes).unwrap();
+        let err = ciborium::from_reader::<TxBuilder, _>(&bytes[..])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing field `version`"));
+    }
+
+    #[test]
+    fn test_lints_missing_lock_data() {
+        let (_, spend_condition, note, recipient) = spend_fixture();
+        let timelocked = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(recipient)),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+
+        let lints = |include_lock_data: bool| {
+            let mut spend = SpendBuilder::new(
+                note.clone(),
+                spend_condition.clone(),
+                Some(spend_condition.clone()),
+            );
+            let seed = spend.build_seed(timelocked.clone(), 1234567, include_lock_data);
+            spend.seed(seed).compute_refund(false);
+            let mut builder = TxBuilder::new(1);
+            builder.spend(spend);
+            builder.lints()
+        };
+
+        assert_eq!(
+            lints(false),
+            vec![TxLint::MissingLockDataForComplexOutput {
+                lock_hash: timelocked.hash()
+            }]
+        );
+        assert!(lints(true).is_empty());
+
+        // Single pkh outputs (here, recipient and refund) don't need lock data.
+        let mut builder = TxBuilder::new(1);
+        builder
+            .simple_spend(
+                vec![(note, spend_condition)],
+                recipient,
+                1234567,
+                recipient,
+                false,
+                None,
+            )
+            .unwrap();
+        assert!(builder.lints().is_empty());
+    }
+
+    #[test]
+    fn test_lints_fee_rate() {
+        let (_, spend_condition, note, recipient) = spend_fixture();
+
+        let mut builder = TxBuilder::new(0);
+        builder
+            .simple_spend(
+                vec![(note.clone(), spend_condition.clone())],
+                recipient,
+                1234567,
+                recipient,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(builder.lints(), vec![TxLint::ZeroFeeRate]);
+
+        // The fee takes most of the note, leaving little for the recipient.
+        let fee = note.assets - 1234567;
+        let mut spend = SpendBuilder::new(note.clone(), spend_condition, None);
+        let seed = spend.build_seed(
+            SpendCondition::new_pkh(Pkh::single(recipient)),
+            1234567,
+            false,
+        );
+        spend.seed(seed).fee(fee);
+        assert!(spend.is_balanced());
+        let mut builder = TxBuilder::new(1 << 15);
+        builder.spend(spend);
+        assert_eq!(
+            builder.lints(),
+            vec![TxLint::ExcessiveFee {
+                fee,
+                inputs_value: note.assets
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lints_refund_not_self_spendable() {
+        let (private_key, own, note, recipient) = spend_fixture();
+
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(
+                vec![(note.clone(), own.clone())],
+                recipient,
+                1234567,
+                recipient,
+                false,
+                None,
+            )
+            .unwrap();
+        // Not checked until the wallet's conditions are known
+        assert_eq!(builder.lints(), vec![]);
+
+        builder.set_own_conditions(vec![own.clone()]);
+        assert_eq!(
+            builder.lints(),
+            vec![TxLint::RefundNotSelfSpendable {
+                name: note.name.clone(),
+                lock_hash: SpendCondition::new_pkh(Pkh::single(recipient)).hash(),
+            }]
+        );
+
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(
+                vec![(note, own.clone())],
+                recipient,
+                1234567,
+                private_key.public_key().hash(),
+                false,
+                None,
+            )
+            .unwrap()
+            .set_own_conditions(vec![own]);
+        assert_eq!(builder.lints(), vec![]);
+    }
+
+    #[test]
+    fn test_change_output_index() {
+        let (private_key, own, note, recipient) = spend_fixture();
+
+        assert_eq!(TxBuilder::new(1 << 15).change_output_index(), None);
+
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(
+                vec![(note.clone(), own.clone())],
+                recipient,
+                1234567,
+                private_key.public_key().hash(),
+                false,
+                None,
+            )
+            .unwrap();
+        let outputs = builder.build().outputs();
+        assert_eq!(outputs.len(), 2);
+        let change = &outputs[builder.change_output_index().unwrap()];
+        assert_eq!(change.name.first, own.first_name());
+        assert_eq!(change.assets, note.assets - 1234567 - builder.cur_fee());
+    }
+
+    #[test]
+    fn test_set_refund_lock() {
+        let (private_key, _) = keys();
+
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let note = fixture_note(&spend_condition, 1u64.hash(), 10000);
+        let old_lock = SpendCondition::new_pkh(Pkh::single(
+            "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                .try_into()
+                .unwrap(),
+        ));
+        let new_lock = SpendCondition::new_pkh(Pkh::single(
+            "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                .try_into()
+                .unwrap(),
+        ));
 
-        assert_eq!(
-            tx.id.to_string(),
-            "3pmkA1knKhJzmd28t5TULP9DADK7GhWsHaNSTpPcGcN4nxzrWsDK2xe",
-        );
+        let recipient = SpendCondition::new_pkh(Pkh::single(
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+        ));
 
-        let mut tx = TxBuilder::new(1 << 17);
+        let mut spend = SpendBuilder::new(note, spend_condition, Some(old_lock.clone()));
+        let seed = spend.build_seed(recipient, 3000, false);
+        spend.seed(seed).fee(1000).compute_refund(false);
+        assert!(spend.sign(&private_key));
+        assert_eq!(spend.cur_refund().unwrap().gift, 6000);
 
-        tx.simple_spend_base(
-            vec![(note.clone(), spend_condition.clone())],
-            recipient,
-            gift,
-            refund_pkh,
-            true,
-            None,
-        )
-        .unwrap()
-        .set_fee_and_balance_refund(fee, false, true)
-        .unwrap()
-        .sign(&private_key);
+        spend
+            .set_refund_lock(Some(new_lock.clone()), false)
+            .unwrap();
+        assert!(spend.is_balanced());
+        assert!(!spend.missing_unlocks().is_empty());
+        let refund = spend.cur_refund().unwrap();
+        assert_eq!(refund.lock_root.hash(), new_lock.hash());
+        assert_eq!(refund.gift, 6000);
+        assert_eq!(spend.spend.seeds().0.len(), 2);
+        assert!(spend
+            .spend
+            .seeds()
+            .0
+            .iter()
+            .all(|v| v.lock_root.hash() != old_lock.hash()));
 
-        assert!(tx.validate().is_err());
+        // Leftover value must go somewhere
+        assert!(matches!(
+            spend.set_refund_lock(None, false),
+            Err(BuildError::UnbalancedSpends)
+        ));
+        assert_eq!(
+            spend.cur_refund().unwrap().lock_root.hash(),
+            new_lock.hash()
+        );
+    }
 
-        let fee_per_word = 40000;
-        let mut builder = TxBuilder::new(fee_per_word);
+    #[test]
+    fn test_total_change() {
+        let (private_key, spend_condition, note, recipient) = spend_fixture();
+        let notes = [note, fixture_note(&spend_condition, 3000u64.hash(), 3000)];
+        let refund_pkh = private_key.public_key().hash();
 
+        let mut builder = TxBuilder::new(1);
         builder
             .simple_spend(
-                vec![(note, spend_condition)],
+                notes.map(|n| (n, spend_condition.clone())).to_vec(),
                 recipient,
-                gift,
+                4294967296 - 1000,
                 refund_pkh,
                 false,
                 None,
             )
             .unwrap();
 
-        let fee1 = builder.calc_fee();
-
-        let tx = builder.sign(&private_key).build();
-
-        assert_eq!(tx.to_raw_tx().spends.fee(fee_per_word), 2520000);
-        assert_eq!(fee1, 2520000);
+        let refund_name = SpendCondition::new_pkh(Pkh::single(refund_pkh)).first_name();
+        let refund_outputs = builder
+            .sign(&private_key)
+            .build()
+            .outputs()
+            .into_iter()
+            .filter(|n| n.name.first == refund_name)
+            .map(|n| n.assets)
+            .sum::<Nicks>();
+
+        let change = builder.total_change();
+        assert!(change > 0);
+        assert_eq!(change, refund_outputs);
+        // The small note stays in the fee pool, as the first one covers the fee
+        assert_eq!(builder.total_change() + builder.cur_fee(), 1000);
+
+        let dust = builder.dust_refunds(3000);
+        assert_eq!(dust.len(), builder.all_spends().len());
+        assert_eq!(dust.iter().map(|(_, v)| v).sum::<Nicks>(), change);
+        assert!(builder.dust_refunds(1).is_empty());
     }
 
     #[test]
@@ -1043,7 +2950,7 @@ mod tests {
             992,
             "{} {:?}",
             builder.fee_pool.len(),
-            builder.spends
+            *builder.spends
         );
         assert_eq!(builder.cur_fee(), 992);
 
@@ -1054,7 +2961,7 @@ mod tests {
             992,
             "{} {:?}",
             builder.fee_pool.len(),
-            builder.spends
+            *builder.spends
         );
         assert_eq!(builder.cur_fee(), 992);
 
@@ -1381,6 +3288,71 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_build_deterministic_across_insertion_orders() {
+        let (private_key, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let recipients = [2u64.hash(), 3u64.hash()];
+
+        // Each spend pays both recipients, so the outputs merge seeds across spends.
+        let spends: Vec<SpendBuilder> = (0..3u64)
+            .map(|i| {
+                let note = Note {
+                    origin_page: 13 + i,
+                    ..fixture_note(&spend_condition, i.hash(), 4294967296)
+                };
+                let mut spend =
+                    SpendBuilder::new(note, spend_condition.clone(), Some(spend_condition.clone()));
+                for (j, recipient) in recipients.iter().enumerate() {
+                    let mut seed = spend.build_seed(
+                        SpendCondition::new_pkh(Pkh::single(*recipient)),
+                        1000 * (i + 1) + j as u64,
+                        true,
+                    );
+                    seed.note_data.push_memo_utf8("memo");
+                    spend.seed(seed);
+                }
+                spend.fee(65536).compute_refund(false);
+                spend
+            })
+            .collect();
+
+        let build = |order: &[usize], reverse_seeds: bool| {
+            let mut builder = TxBuilder::new(1 << 15);
+            for &i in order {
+                let mut spend = spends[i].clone();
+                if reverse_seeds {
+                    spend.spend.seeds_mut().0.reverse();
+                }
+                builder.spend(spend);
+            }
+            builder.sign(&private_key);
+            builder.build()
+        };
+
+        let tx = build(&[0, 1, 2], false);
+        let outputs: Vec<Digest> = tx.outputs().iter().map(|v| v.hash()).collect();
+        assert_eq!(outputs.len(), 3);
+        for (order, reverse_seeds) in [
+            ([2, 1, 0], false),
+            ([1, 2, 0], false),
+            ([0, 1, 2], true),
+            ([2, 0, 1], true),
+        ] {
+            let other = build(&order, reverse_seeds);
+            assert_eq!(
+                other.id, tx.id,
+                "order {order:?}, reverse_seeds {reverse_seeds}"
+            );
+            assert!(other.canonical_eq(&tx));
+            assert_eq!(
+                other.outputs().iter().map(|v| v.hash()).collect::<Vec<_>>(),
+                outputs
+            );
+            assert_eq!(other.to_noun(), tx.to_noun());
+        }
+    }
+
     #[test]
     fn test_multiseed_outputs() {
         let (private_key, public_key) = keys();
@@ -1434,6 +3406,11 @@ mod tests {
             .unwrap();
         let gift = 4294967296 * 3 - 65536 * 100;
         let refund_pkh = public_key.hash();
+        let first_note = notes[0].clone();
+        let first_lock = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(public_key.hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
 
         let tx = TxBuilder::new(1 << 15)
             .simple_spend_base(
@@ -1496,9 +3473,128 @@ mod tests {
                 "97ieQ5D2FafHMx6L29f9EvY1aKdmb4Z27TfXA6MtViCncjizMVzTZ7d".to_string()
             )
         );
+
+        let seeds: Vec<&Seed> = tx
+            .spends
+            .0
+            .iter()
+            .flat_map(|(_, spend)| spend.seeds().0.iter())
+            .collect();
+        for output in &outputs {
+            let output_seeds: Vec<&Seed> = seeds
+                .iter()
+                .copied()
+                .filter(|s| (true, s.lock_root.hash()).hash() == output.name.first)
+                .collect();
+            assert_eq!(
+                Seed::merged_output_name(&output_seeds),
+                Some(output.name.clone())
+            );
+            // Merged outputs don't match any of their seeds alone.
+            assert_eq!(
+                output_seeds.iter().any(|s| output.matches_seed(s)),
+                output_seeds.len() == 1
+            );
+        }
+        assert!(outputs.iter().any(|output| {
+            seeds
+                .iter()
+                .filter(|s| (true, s.lock_root.hash()).hash() == output.name.first)
+                .count()
+                > 1
+        }));
+
+        // With a single input, every output comes from a single seed.
+        let tx = TxBuilder::new(1 << 15)
+            .simple_spend_base(
+                vec![(first_note, first_lock)],
+                recipient,
+                65536,
+                refund_pkh,
+                false,
+                None,
+            )
+            .unwrap()
+            .build();
+        let outputs = tx.outputs();
+        assert_eq!(outputs.len(), 2);
+        let seeds = &tx.spends.0[0].1.seeds().0;
+        for output in &outputs {
+            assert_eq!(seeds.iter().filter(|s| output.matches_seed(s)).count(), 1);
+        }
         // TODO: test note-data order
     }
 
+    #[test]
+    fn test_describe() {
+        let (private_key, _, _, recipient) = spend_fixture();
+        let public_key = private_key.public_key();
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(public_key.hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let notes = (0..3u64)
+            .map(|i| {
+                let note = Note {
+                    origin_page: 13 + i,
+                    ..fixture_note(&spend_condition, i.hash(), 4294967296)
+                };
+                (note, spend_condition.clone())
+            })
+            .collect();
+
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend_base(
+                notes,
+                recipient,
+                4294967296 * 3 - 65536 * 100,
+                public_key.hash(),
+                false,
+                None,
+            )
+            .unwrap()
+            .recalc_and_set_fee(false)
+            .unwrap();
+
+        let report = builder.describe();
+        assert_eq!(report.inputs.len(), 3);
+        assert_eq!(report.outputs.len(), 2);
+        assert_eq!(report.fee, builder.calc_fee());
+        assert_eq!(report.needed_fee, report.fee);
+        assert!(report.balanced);
+        assert_eq!(report.missing_unlocks.len(), 3);
+        assert_eq!(
+            report.inputs[0].lock,
+            vec![
+                LockClause::Multisig { m: 1, n: 1 },
+                LockClause::Timelock {
+                    rel_min: Some(100),
+                    rel_max: None,
+                    abs_min: None,
+                    abs_max: None,
+                },
+            ]
+        );
+
+        let recipient_root = SpendCondition::new_pkh(Pkh::single(recipient)).hash();
+        let output = report
+            .outputs
+            .iter()
+            .find(|o| o.lock_root == recipient_root)
+            .unwrap();
+        assert_eq!(output.gift, 4294967296 * 3 - 65536 * 100);
+        assert_eq!(output.lock, Some(vec![LockClause::Multisig { m: 1, n: 1 }]));
+        assert!(!output.has_memo && !output.has_lock_data);
+        assert_eq!(
+            report.outputs.iter().map(|o| o.gift).sum::<Nicks>() + report.fee,
+            4294967296 * 3
+        );
+
+        builder.sign(&private_key);
+        assert!(builder.describe().missing_unlocks.is_empty());
+    }
+
     #[test]
     fn test_missing_unlock() {
         let (private_key, _) = keys();
@@ -1632,6 +3728,357 @@ mod tests {
             }]
         );
     }
+    #[test]
+    fn test_calc_fee_cache() {
+        let (private_key, spend_condition, note, _) = spend_fixture();
+        let public_key = private_key.public_key();
+        let mut other_note = note.clone();
+        other_note.name = Name::new(spend_condition.first_name(), 1u64.hash());
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend_base(
+                vec![(note, spend_condition.clone())],
+                public_key.hash(),
+                1234567,
+                public_key.hash(),
+                false,
+                None,
+            )
+            .unwrap();
+
+        let fee = builder.calc_fee();
+        assert_eq!(builder.spends.fee.get(), Some(&fee));
+        assert_eq!(builder.calc_fee(), fee);
+
+        // Any mutation clears the cache, and the fee is recomputed.
+        builder.sign(&private_key);
+        assert_eq!(builder.spends.fee.get(), None);
+        assert_eq!(builder.calc_fee(), fee);
+
+        let mut spend = SpendBuilder::new(
+            other_note,
+            spend_condition.clone(),
+            Some(spend_condition.clone()),
+        );
+        spend.compute_refund(false);
+        builder.spend(spend);
+        assert!(builder.calc_fee() > fee);
+
+        let mut uncached = builder.clone();
+        uncached.spends.as_map_mut();
+        assert_eq!(uncached.calc_fee(), builder.calc_fee());
+    }
+
+    #[test]
+    fn test_strip_refund_lock_data() {
+        let (private_key, spend_condition, note, recipient) = spend_fixture();
+        let public_key = private_key.public_key();
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(
+                vec![(note, spend_condition)],
+                recipient,
+                1234567,
+                public_key.hash(),
+                true,
+                None,
+            )
+            .unwrap()
+            .sign(&private_key)
+            .validate()
+            .unwrap();
+        let fee = builder.cur_fee();
+
+        builder.strip_refund_lock_data().unwrap();
+        assert!(builder.cur_fee() < fee);
+        assert_eq!(builder.cur_fee(), builder.calc_fee());
+        assert!(!builder.is_fully_signed());
+
+        let outputs = builder.build().outputs();
+        let has_lock_data = |lock: Digest| {
+            outputs
+                .iter()
+                .find(|n| n.name.first == SpendCondition::new_pkh(Pkh::single(lock)).first_name())
+                .unwrap()
+                .note_data
+                .has_lock()
+        };
+        assert!(has_lock_data(recipient));
+        assert!(!has_lock_data(public_key.hash()));
+
+        builder.sign(&private_key).validate().unwrap();
+    }
+
+    #[test]
+    fn test_can_afford() {
+        let (_, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let notes: Vec<_> = (0..2u64)
+            .map(|i| {
+                (
+                    fixture_note(&spend_condition, i.hash(), 4294967296),
+                    spend_condition.clone(),
+                )
+            })
+            .collect();
+        let total = 2 * 4294967296;
+
+        assert!(TxBuilder::can_afford(&notes, total, 0));
+        assert!(!TxBuilder::can_afford(&notes, total, 1));
+        assert!(!TxBuilder::can_afford(&notes, total + 1, 0));
+        assert!(!TxBuilder::can_afford(&[], 1, 0));
+
+        let mut builder = TxBuilder::new(1 << 15);
+        assert!(matches!(
+            builder.simple_spend(
+                notes.clone(),
+                2u64.hash(),
+                total + 1,
+                public_key.hash(),
+                false,
+                None
+            ),
+            Err(BuildError::InsufficientFunds)
+        ));
+        // Nothing was added before failing.
+        assert!(builder.all_spends().is_empty());
+        assert!(builder.fee_pool_notes().is_empty());
+
+        let mut builder = TxBuilder::new(1 << 15);
+        builder
+            .simple_spend(notes, 2u64.hash(), 1234567, public_key.hash(), false, None)
+            .unwrap();
+        assert!(TxBuilder::can_afford(
+            &builder.all_notes().into_values().collect::<Vec<_>>(),
+            1234567,
+            builder.cur_fee()
+        ));
+    }
+
+    #[test]
+    fn test_simulate_spend() {
+        let (_, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let mut note_data = NoteData::empty();
+        note_data.push_lock(spend_condition.clone());
+        let notes: Vec<(Note, SpendCondition)> = [3u64, 1, 4, 2]
+            .into_iter()
+            .map(|i| {
+                let note = Note {
+                    note_data: note_data.clone(),
+                    ..fixture_note(&spend_condition, i.hash(), i * 1_000_000_000)
+                };
+                (note, spend_condition.clone())
+            })
+            .collect();
+        let mut balance = Balance(
+            notes
+                .iter()
+                .map(|(note, _)| (note.name.clone(), note.clone()))
+                .collect(),
+        );
+        // Without lock data, this note can't be planned for.
+        let unplannable = fixture_note(&spend_condition, 5u64.hash(), 5_000_000_000);
+        balance.0.push((unplannable.name.clone(), unplannable));
+
+        let gift = 4_500_000_000;
+        for (selection, order) in [
+            (CoinSelection::InOrder, [0, 1, 2, 3]),
+            (CoinSelection::LargestFirst, [2, 0, 3, 1]),
+            (CoinSelection::SmallestFirst, [1, 3, 0, 2]),
+        ] {
+            let plan = balance.simulate_spend(gift, 1 << 15, selection).unwrap();
+
+            let mut builder = TxBuilder::new(1 << 15);
+            builder
+                .simple_spend(
+                    order.iter().map(|&i| notes[i].clone()).collect(),
+                    2u64.hash(),
+                    gift,
+                    public_key.hash(),
+                    false,
+                    None,
+                )
+                .unwrap();
+            let inputs: Vec<Name> = builder.all_spends().keys().cloned().collect();
+            assert_eq!(plan.inputs, inputs, "{selection:?}");
+            assert_eq!(plan.fee, builder.cur_fee());
+            assert_eq!(plan.change, builder.total_change());
+            assert_eq!(plan.selected, gift + plan.fee + plan.change);
+        }
+
+        let plan = balance
+            .simulate_spend(gift, 1 << 15, CoinSelection::LargestFirst)
+            .unwrap();
+        assert_eq!(plan.inputs.len(), 2);
+
+        assert!(matches!(
+            balance.simulate_spend(10_000_000_000, 1 << 15, CoinSelection::InOrder),
+            Err(BuildError::InsufficientFunds)
+        ));
+    }
+
+    #[test]
+    fn test_merge() {
+        let (private_key, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let builder = |i: u64, fee_per_word: Nicks| {
+            let mut builder = TxBuilder::new(fee_per_word);
+            builder
+                .simple_spend(
+                    vec![(
+                        fixture_note(&spend_condition, i.hash(), 4294967296),
+                        spend_condition.clone(),
+                    )],
+                    (i + 10).hash(),
+                    1234567,
+                    public_key.hash(),
+                    false,
+                    None,
+                )
+                .unwrap();
+            builder
+        };
+
+        let mut merged = builder(0, 1 << 15);
+        merged.merge(builder(1, 1 << 15)).unwrap();
+        assert_eq!(merged.all_spends().len(), 2);
+        merged
+            .recalc_and_set_fee(false)
+            .unwrap()
+            .sign(&private_key)
+            .validate()
+            .unwrap();
+        let tx = merged.build();
+        assert_eq!(tx.to_raw_tx().input_names().len(), 2);
+        // Both recipients and the shared refund address.
+        assert_eq!(tx.outputs().len(), 3);
+
+        let mut ours = builder(0, 1 << 15);
+        assert!(matches!(
+            ours.merge(builder(0, 1 << 15)),
+            Err(BuildError::DuplicateNote(_))
+        ));
+        assert!(matches!(
+            ours.merge(builder(1, 1 << 14)),
+            Err(BuildError::FeeRateMismatch(_, _))
+        ));
+        assert_eq!(ours.all_spends().len(), 1);
+    }
+
+    #[test]
+    fn test_preview_recipient_output() {
+        let (_, public_key) = keys();
+        let spend_condition = SpendCondition::new_pkh(Pkh::single(public_key.hash()));
+        let notes: Vec<_> = (0..2u64)
+            .map(|i| {
+                (
+                    fixture_note(&spend_condition, i.hash(), 4294967296),
+                    spend_condition.clone(),
+                )
+            })
+            .collect();
+        let recipient = 2u64.hash();
+        // Spans both notes, so the recipient's output merges two seeds.
+        let gift = 4294967296 + 1234567;
+
+        for include_lock_data in [false, true] {
+            let mut builder = TxBuilder::new(1 << 15);
+            for (note, spend_condition) in notes.iter().cloned() {
+                builder
+                    .add_to_fee_pool(note, spend_condition.clone(), spend_condition, false)
+                    .unwrap();
+            }
+            let fee_pool = builder.fee_pool_notes();
+            let preview = builder
+                .preview_recipient_output(recipient, gift, include_lock_data)
+                .unwrap();
+            assert_eq!(builder.fee_pool_notes(), fee_pool);
+            assert!(builder.all_spends().is_empty());
+            assert_eq!(preview.assets, gift);
+            assert_eq!(
+                preview.note_data.extract_lock().unwrap().is_some(),
+                include_lock_data,
+                "include_lock_data = {include_lock_data}"
+            );
+
+            let mut builder = TxBuilder::new(1 << 15);
+            builder
+                .simple_spend(
+                    notes.clone(),
+                    recipient,
+                    gift,
+                    public_key.hash(),
+                    include_lock_data,
+                    None,
+                )
+                .unwrap();
+            let output = builder
+                .build()
+                .outputs()
+                .into_iter()
+                .find(|note| note.name == preview.name)
+                .unwrap();
+            assert_eq!(output.hash(), preview.hash());
+        }
+
+        let builder = TxBuilder::new(1 << 15);
+        assert!(matches!(
+            builder.preview_recipient_output(recipient, gift, false),
+            Err(BuildError::InsufficientFunds)
+        ));
+    }
+
+    #[test]
+    fn test_reset_signatures() {
+        use crate::Hax;
+        let (private_key, public_key) = keys();
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(public_key.hash())),
+            LockPrimitive::Hax(Hax(vec![0.to_noun().hash()])),
+        ]);
+        let note = fixture_note(&spend_condition, 1u64.hash(), 4294967296);
+        let mut builder = TxBuilder::new(1);
+        builder
+            .simple_spend_base(
+                vec![(note, spend_condition)],
+                public_key.hash(),
+                1234567,
+                public_key.hash(),
+                false,
+                None,
+            )
+            .unwrap();
+        assert!(!builder.is_fully_signed());
+
+        let missing = |builder: &TxBuilder| {
+            builder
+                .all_spends()
+                .values()
+                .flat_map(|v| v.missing_unlocks())
+                .collect::<Vec<_>>()
+        };
+        builder.add_preimage(0.to_noun());
+        builder.sign(&private_key);
+        assert!(builder.is_fully_signed());
+        assert!(missing(&builder).is_empty());
+
+        builder.reset_signatures(false);
+        assert!(!builder.is_fully_signed());
+        assert_eq!(
+            missing(&builder),
+            vec![MissingUnlocks::Pkh {
+                num_sigs: 1,
+                sig_of: [public_key.hash()].into_iter().collect()
+            }]
+        );
+
+        builder.sign(&private_key).reset_signatures(true);
+        assert!(!builder.is_fully_signed());
+        assert_eq!(missing(&builder).len(), 2);
+        assert!(matches!(missing(&builder)[1], MissingUnlocks::Hax { .. }));
+    }
+
     #[test]
     fn test_jam_vector() {
         let (private_key, _) = keys();
@@ -1688,5 +4135,12 @@ mod tests {
             bs58::encode(jam_vec).into_string(),
             "3gBbvwuhALLvTWnLfgP3KVWz2qSWKsvLXHmFAKXfqYjiNiu1Xc32GguLGUTzfEFyWMCfWuxurCkmgUaXnWJEoWdX62tiTwmdXPhJzcEgDeoy99rmZyezkHK992jinuFNmDEDEvVd5vM19g7MRNRi5d3zWPtjCL2j9JyfT6mtTKgh9PNnWLY75A2JwzUDd6FSytomgVBeyqhjBWm7tMgkXngduhJGoZ6rS5MkyrzFhmtAYmtjVV9p4HnjDW6rrtgKXLEqUp3jpEdxXA4nHT8mtbSAxNvvQF5V4wmYddKDrzCPeWd8mccHUnsSxWLLRgEbYgUHvC6Wh5F5nKsEb6zvT9jGB9s9etXPYknTRBHmsDBWBveCmAzVy6Fa2x8iNuc15NPmQQwbbGZsmjGbVQKFT8vJz7HjcefhEZg9zbyq9BhQ3u6gY8vYqETL5u8wCvRb9bkNMkUEBcsNnkfmeXQcSdaYfTaExQFPpdLDkBPcG4bHTffXsgEwRxFpLXRWgzzM5ESBYZvKyEtk32tUodnsbQ9zun2mptmFq6zLW6kLhDwKBT6rR3ErddCE82p5qcUaC4ZLR3fiz59Hg14MQeYnBkAy7Cj3Z7WdqvfPoXhZZ2FCztn9SZXeLFxotFZNqeHp9PQu754PnCq1rUpgCUcnoQiWwyjEP7JbY6T9hLyA3m7T6b97DbEqD7iuDNwrhwbofKyyfPxFeZKap",
         );
+
+        let raw_tx = tx.to_raw_tx();
+        assert_eq!(raw_tx.input_names(), vec![note.name]);
+        let output_locks = raw_tx.distinct_output_locks();
+        assert_eq!(output_locks.len(), 2);
+        assert_eq!(output_locks.len(), raw_tx.outputs().len());
+        assert!(output_locks.contains(&SpendCondition::new_pkh(Pkh::single(recipient)).hash()));
     }
 }
@@ -1,7 +1,13 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 pub mod builder;
 pub mod note;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod tx;
 
 pub use builder::*;
 pub use note::*;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
 pub use tx::*;
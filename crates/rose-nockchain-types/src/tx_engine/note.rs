@@ -2,11 +2,12 @@ use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use alloc::{string::String, vec};
-use rose_ztd::{Digest, Hashable, Noun, NounDecode, NounEncode, ZSet};
+use rose_ztd::{Digest, Hashable, Noun, NounDecode, NounEncode, ZSet, PRIME};
 use rose_ztd_derive::{Hashable, NounDecode, NounEncode};
 use serde::{Deserialize, Serialize};
 
-use super::SpendCondition;
+use super::{LockClause, LockError, Seed, SpendCondition};
+use crate::{LOCK_KEY, MEMO_KEY};
 
 /// Memo encoded as `(list @ux)` (a null-terminated list of byte atoms), matching nockchain CLI.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -128,8 +129,6 @@ impl Hashable for NoteDataEntry {
     }
 }
 
-pub const MEMO_KEY: &str = "memo";
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoteData {
     pub entries: Vec<NoteDataEntry>,
@@ -144,7 +143,7 @@ impl NoteData {
 
     pub fn push_pkh(&mut self, pkh: Pkh) {
         self.entries.push(NoteDataEntry {
-            key: "lock".to_string(),
+            key: LOCK_KEY.to_string(),
             val: (0, ("pkh", &pkh), 0).to_noun(),
         });
     }
@@ -152,7 +151,7 @@ impl NoteData {
     // TODO: support 2,4,8,16-way spend conditions.
     pub fn push_lock(&mut self, spend_condition: SpendCondition) {
         self.entries.push(NoteDataEntry {
-            key: "lock".to_string(),
+            key: LOCK_KEY.to_string(),
             val: (0, spend_condition).to_noun(),
         });
     }
@@ -177,6 +176,49 @@ impl NoteData {
     pub fn push_memo_utf8(&mut self, memo: &str) {
         self.push_memo_bytes(MemoBytes::from_utf8(memo));
     }
+
+    /// Whether the note-data has a lock entry (see [`NoteData::push_lock`]).
+    pub fn has_lock(&self) -> bool {
+        self.entries.iter().any(|e| e.key == LOCK_KEY)
+    }
+
+    /// Lock stored in the note-data (see [`NoteData::push_lock`]), decoded and validated.
+    ///
+    /// Returns `Ok(None)` if there is no lock entry.
+    pub fn extract_lock(&self) -> Result<Option<SpendCondition>, LockError> {
+        let Some(entry) = self.entries.iter().find(|e| e.key == LOCK_KEY) else {
+            return Ok(None);
+        };
+        let (_version, lock): (u64, Noun) =
+            NounDecode::from_noun(&entry.val).ok_or(LockError::Decode)?;
+        SpendCondition::try_from_noun(&lock).map(Some)
+    }
+
+    pub fn memo(&self) -> Option<&Noun> {
+        self.entries
+            .iter()
+            .find(|e| e.key == MEMO_KEY)
+            .map(|e| &e.val)
+    }
+
+    /// Note-data of an output note built from `seeds`, given in z-set order.
+    ///
+    /// This is the note-data of the last seed, as in the tx engine. Memo placement depends on
+    /// seed ordering though, so if the last seed has no memo, the memo of the last seed carrying
+    /// one is kept, so that wallet UIs don't silently drop it. Other entries of earlier seeds are
+    /// dropped, like the tx engine does.
+    pub fn merge_for_output(seeds: &[&Seed]) -> NoteData {
+        let Some(last) = seeds.last() else {
+            return NoteData::empty();
+        };
+        let mut ret = last.note_data.clone();
+        if ret.memo().is_none() {
+            if let Some(memo) = seeds.iter().rev().find_map(|s| s.note_data.memo()) {
+                ret.push_memo(memo.clone());
+            }
+        }
+        ret
+    }
 }
 
 impl NounEncode for NoteData {
@@ -223,6 +265,154 @@ impl Note {
             assets,
         }
     }
+
+    /// Attach a memo to the note, replacing any existing one.
+    pub fn with_memo(mut self, memo: Noun) -> Note {
+        self.note_data.entries.retain(|e| e.key != MEMO_KEY);
+        self.note_data.push_memo(memo);
+        self
+    }
+
+    pub fn memo(&self) -> Option<&Noun> {
+        self.note_data.memo()
+    }
+
+    /// Kind of the lock stored in the note-data, if any (see [`NoteData::extract_lock`]).
+    pub fn lock_kind(&self) -> Option<LockKind> {
+        let lock = self.note_data.extract_lock().ok()??;
+        Some(LockKind::from(&lock))
+    }
+
+    /// Whether this note is the output of `seed`, alone.
+    ///
+    /// Outputs merged from several seeds sharing a lock root should instead be checked against
+    /// [`Seed::merged_output_name`].
+    pub fn matches_seed(&self, seed: &Seed) -> bool {
+        self.name == seed.expected_output_name()
+    }
+}
+
+impl Note {
+    /// Start building a note, with validation of its invariants.
+    pub fn builder() -> NoteBuilder {
+        NoteBuilder::new()
+    }
+}
+
+/// Builder for [`Note`], which checks note invariants upon [`NoteBuilder::build`].
+#[derive(Debug, Clone)]
+pub struct NoteBuilder {
+    version: Version,
+    origin_page: BlockHeight,
+    name: Option<Name>,
+    lock: Option<SpendCondition>,
+    note_data: NoteData,
+    assets: Nicks,
+}
+
+impl Default for NoteBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoteBuilder {
+    /// Create a builder for a V1 note with empty note-data.
+    pub fn new() -> Self {
+        Self {
+            version: Version::V1,
+            origin_page: 0,
+            name: None,
+            lock: None,
+            note_data: NoteData::empty(),
+            assets: 0,
+        }
+    }
+
+    pub fn version(&mut self, version: Version) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    pub fn origin_page(&mut self, origin_page: BlockHeight) -> &mut Self {
+        self.origin_page = origin_page;
+        self
+    }
+
+    pub fn name(&mut self, name: Name) -> &mut Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Set the spend condition locking this note.
+    ///
+    /// For V1+ notes, the name's first component must commit to this lock.
+    pub fn lock(&mut self, lock: SpendCondition) -> &mut Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    pub fn note_data(&mut self, note_data: NoteData) -> &mut Self {
+        self.note_data = note_data;
+        self
+    }
+
+    pub fn assets(&mut self, assets: Nicks) -> &mut Self {
+        self.assets = assets;
+        self
+    }
+
+    pub fn build(&self) -> Result<Note, NoteError> {
+        let name = self.name.clone().ok_or(NoteError::MissingName)?;
+        if self.assets == 0 {
+            return Err(NoteError::ZeroAssets);
+        }
+        if self.assets >= PRIME {
+            return Err(NoteError::AssetsOutOfRange(self.assets));
+        }
+        if let Some(lock) = &self.lock {
+            if self.version == Version::V0 {
+                return Err(NoteError::LockOnLegacyNote);
+            }
+            if name.first != lock.first_name() {
+                return Err(NoteError::NameLockMismatch);
+            }
+        }
+        Ok(Note::new(
+            self.version.clone(),
+            self.origin_page,
+            name,
+            self.note_data.clone(),
+            self.assets,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteError {
+    MissingName,
+    ZeroAssets,
+    AssetsOutOfRange(Nicks),
+    LockOnLegacyNote,
+    NameLockMismatch,
+}
+
+impl core::fmt::Display for NoteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NoteError::MissingName => write!(f, "Note name is not set"),
+            NoteError::ZeroAssets => write!(f, "Note must hold nonzero assets"),
+            NoteError::AssetsOutOfRange(assets) => {
+                write!(f, "Note assets ({assets}) do not fit in a field element")
+            }
+            NoteError::LockOnLegacyNote => {
+                write!(f, "Legacy (V0) notes do not commit to a spend condition")
+            }
+            NoteError::NameLockMismatch => {
+                write!(f, "Note name does not match its spend condition")
+            }
+        }
+    }
 }
 
 pub type Nicks = u64;
@@ -230,6 +420,50 @@ pub type Nicks = u64;
 #[derive(Debug, Clone)]
 pub struct Balance(pub Vec<(Name, Note)>);
 
+impl Balance {
+    /// Notes whose lock is of the given kind. Notes without lock data are never included.
+    pub fn filter_by_lock_kind(&self, kind: LockKind) -> Balance {
+        Balance(
+            self.0
+                .iter()
+                .filter(|(_, note)| note.lock_kind() == Some(kind))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// Coarse kind of a lock, for grouping notes (see [`Note::lock_kind`]).
+///
+/// A lock combining several primitives gets the most restrictive kind, in order
+/// `Burn > Hashlocked > Timelocked > Multisig > SimplePkh`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LockKind {
+    /// Single signature
+    SimplePkh,
+    /// More than one allowed, or required, signer
+    Multisig,
+    Timelocked,
+    Hashlocked,
+    Burn,
+}
+
+impl From<&SpendCondition> for LockKind {
+    fn from(lock: &SpendCondition) -> Self {
+        lock.describe()
+            .iter()
+            .map(|clause| match clause {
+                LockClause::Multisig { m: 1, n: 1 } => LockKind::SimplePkh,
+                LockClause::Multisig { .. } => LockKind::Multisig,
+                LockClause::Timelock { .. } => LockKind::Timelocked,
+                LockClause::Hashlock { .. } => LockKind::Hashlocked,
+                LockClause::Burn => LockKind::Burn,
+            })
+            .max()
+            .unwrap_or(LockKind::SimplePkh)
+    }
+}
+
 pub type BlockHeight = u64;
 
 #[derive(Debug, Clone)]
@@ -339,7 +573,7 @@ pub struct Source {
 }
 
 /// Timelock range (for both absolute and relative constraints)
-#[derive(Debug, Clone, Hashable, NounEncode, NounDecode)]
+#[derive(Debug, Clone, PartialEq, Eq, Hashable, NounEncode, NounDecode)]
 pub struct TimelockRange {
     pub min: Option<BlockHeight>,
     pub max: Option<BlockHeight>,
@@ -352,6 +586,22 @@ impl TimelockRange {
         Self { min, max }
     }
 
+    /// Like [`TimelockRange::new`], but rejects a range with `min > max`, which no block height
+    /// can ever satisfy.
+    pub fn try_new(min: Option<BlockHeight>, max: Option<BlockHeight>) -> Result<Self, LockError> {
+        let range = Self::new(min, max);
+        range.validate()?;
+        Ok(range)
+    }
+
+    /// Check that the range is satisfiable, i.e. not `min > max`.
+    pub fn validate(&self) -> Result<(), LockError> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(LockError::InvertedTimelock { min, max }),
+            _ => Ok(()),
+        }
+    }
+
     pub fn none() -> Self {
         Self {
             min: None,
@@ -359,3 +609,250 @@ impl TimelockRange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Hax, LockPrimitive, LockTim};
+
+    fn lock() -> SpendCondition {
+        SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(
+                "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                    .try_into()
+                    .unwrap(),
+            )),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ])
+    }
+
+    #[test]
+    fn test_note_builder() {
+        let lock = lock();
+        let name = Name::new(
+            lock.first_name(),
+            "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                .try_into()
+                .unwrap(),
+        );
+        let note = Note::builder()
+            .origin_page(13)
+            .name(name.clone())
+            .lock(lock)
+            .assets(4294967296)
+            .build()
+            .unwrap();
+        assert_eq!(note.version, Version::V1);
+        assert_eq!(note.origin_page, 13);
+        assert_eq!(note.name, name);
+        assert_eq!(note.assets, 4294967296);
+        assert!(note.note_data.entries.is_empty());
+    }
+
+    #[test]
+    fn test_note_serde_stability() {
+        // Persisted wallet state relies on this, so changes must be deliberate.
+        let lock = lock();
+        let note = Note::builder()
+            .origin_page(13)
+            .name(Name::new(lock.first_name(), lock.hash()))
+            .lock(lock)
+            .assets(4294967296)
+            .build()
+            .unwrap();
+        let json = r#"{"version":"V1","origin_page":13,"name":{"first":[3946683227555363130,394862963960219136,12349817261495120608,15748433402616070299,1853080136898617451],"last":[12711264525626858578,15050465173325282868,7181959667726041194,4839122055683360653,6876816484758565066],"_sig":0},"note_data":{"entries":[]},"assets":4294967296}"#;
+        assert_eq!(serde_json::to_string(&note).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<Note>(json).unwrap().hash(),
+            note.hash()
+        );
+    }
+
+    #[test]
+    fn test_note_builder_rejects_invalid() {
+        let lock = lock();
+        let name = Name::new(lock.first_name(), lock.hash());
+
+        assert_eq!(
+            Note::builder().assets(1).build().unwrap_err(),
+            NoteError::MissingName
+        );
+        assert_eq!(
+            Note::builder().name(name.clone()).build().unwrap_err(),
+            NoteError::ZeroAssets
+        );
+        assert_eq!(
+            Note::builder()
+                .name(name.clone())
+                .assets(PRIME)
+                .build()
+                .unwrap_err(),
+            NoteError::AssetsOutOfRange(PRIME)
+        );
+        assert_eq!(
+            Note::builder()
+                .name(Name::new(lock.hash(), lock.hash()))
+                .lock(lock.clone())
+                .assets(1)
+                .build()
+                .unwrap_err(),
+            NoteError::NameLockMismatch
+        );
+        assert_eq!(
+            Note::builder()
+                .version(Version::V0)
+                .name(name)
+                .lock(lock)
+                .assets(1)
+                .build()
+                .unwrap_err(),
+            NoteError::LockOnLegacyNote
+        );
+    }
+
+    #[test]
+    fn test_timelock_range_try_new() {
+        assert_eq!(
+            TimelockRange::try_new(Some(100), Some(50)),
+            Err(LockError::InvertedTimelock { min: 100, max: 50 })
+        );
+        assert_eq!(
+            TimelockRange::try_new(Some(50), Some(100)),
+            Ok(TimelockRange::new(Some(50), Some(100)))
+        );
+        assert!(TimelockRange::try_new(Some(100), Some(100)).is_ok());
+        assert!(TimelockRange::try_new(Some(100), None).is_ok());
+        // zero means unbounded
+        assert!(TimelockRange::try_new(Some(100), Some(0)).is_ok());
+    }
+
+    #[test]
+    fn test_extract_lock() {
+        let mut note_data = NoteData::empty();
+        assert!(matches!(note_data.extract_lock(), Ok(None)));
+        note_data.push_lock(lock());
+        assert_eq!(
+            note_data.extract_lock().unwrap().map(|l| l.hash()),
+            Some(lock().hash())
+        );
+
+        let mut note_data = NoteData::empty();
+        note_data.push_lock(SpendCondition(vec![]));
+        assert!(matches!(note_data.extract_lock(), Err(LockError::Empty)));
+    }
+
+    #[test]
+    fn test_filter_by_lock_kind() {
+        let pkh = |i: u64| Pkh::single(i.hash());
+        let note = |i: u64, note_data: NoteData| {
+            let name = Name::new(i.hash(), i.hash());
+            (name.clone(), Note::new(Version::V1, 13, name, note_data, 1))
+        };
+        let with_lock = |lock: SpendCondition| {
+            let mut note_data = NoteData::empty();
+            note_data.push_lock(lock);
+            note_data
+        };
+
+        let balance = Balance(vec![
+            note(1, NoteData::from_pkh(pkh(1))),
+            note(
+                2,
+                NoteData::from_pkh(Pkh::new(2, vec![1u64.hash(), 2u64.hash()])),
+            ),
+            note(3, with_lock(lock())),
+            note(4, with_lock(lock())),
+            note(
+                5,
+                with_lock(SpendCondition(vec![
+                    LockPrimitive::Pkh(pkh(5)),
+                    LockPrimitive::Hax(Hax(vec![5u64.hash()])),
+                ])),
+            ),
+            note(6, NoteData::empty()),
+        ]);
+        assert_eq!(balance.0[0].1.lock_kind(), Some(LockKind::SimplePkh));
+        assert_eq!(balance.0[5].1.lock_kind(), None);
+
+        let count = |kind| balance.filter_by_lock_kind(kind).0.len();
+        assert_eq!(count(LockKind::SimplePkh), 1);
+        assert_eq!(count(LockKind::Multisig), 1);
+        assert_eq!(count(LockKind::Timelocked), 2);
+        assert_eq!(count(LockKind::Hashlocked), 1);
+        assert_eq!(count(LockKind::Burn), 0);
+    }
+
+    #[test]
+    fn test_note_with_memo() {
+        let lock = lock();
+        let note = Note::new(
+            Version::V1,
+            13,
+            Name::new(lock.first_name(), lock.hash()),
+            NoteData::empty(),
+            4294967296,
+        );
+        assert!(note.memo().is_none());
+
+        let memo = MemoBytes::from_utf8("hello").to_noun();
+        let with_memo = note.clone().with_memo(memo.clone());
+        assert_eq!(with_memo.memo().map(|m| m.hash()), Some(memo.hash()));
+        assert_ne!(with_memo.hash(), note.hash());
+
+        // Replaces, rather than adds another memo entry.
+        let other = MemoBytes::from_utf8("world").to_noun();
+        let replaced = with_memo.with_memo(other.clone());
+        assert_eq!(replaced.note_data.entries.len(), 1);
+        assert_eq!(replaced.memo().map(|m| m.hash()), Some(other.hash()));
+    }
+
+    #[test]
+    fn test_merge_for_output_last_seed_wins() {
+        let seed = |memo: Option<&str>, lock_data: bool| {
+            let mut note_data = NoteData::empty();
+            if let Some(memo) = memo {
+                note_data.push_memo_utf8(memo);
+            }
+            if lock_data {
+                note_data.push_lock(lock());
+            }
+            Seed {
+                output_source: None,
+                lock_root: crate::LockRoot::Lock(lock()),
+                note_data,
+                gift: 1,
+                parent_hash: 0u64.hash(),
+            }
+        };
+        let first = seed(Some("first"), true);
+        let second = seed(None, false);
+        let third = seed(Some("third"), false);
+
+        let memo = |nd: &NoteData| nd.memo().map(|m| m.hash());
+
+        let merged = NoteData::merge_for_output(&[&first, &second, &third]);
+        assert_eq!(merged.hash(), third.note_data.hash());
+
+        // A memo on an earlier seed survives if later seeds carry none, but nothing else does.
+        let merged = NoteData::merge_for_output(&[&first, &second]);
+        assert_eq!(merged.entries.len(), 1);
+        assert_eq!(memo(&merged), memo(&first.note_data));
+
+        // Conflicting keys of earlier seeds don't override the last seed's.
+        let mut earlier = seed(None, false);
+        earlier.note_data.push_lock(lock());
+        earlier.note_data.entries.push(NoteDataEntry {
+            key: "custom".into(),
+            val: 1u64.to_noun(),
+        });
+        let mut last = seed(None, false);
+        last.note_data.entries.push(NoteDataEntry {
+            key: "custom".into(),
+            val: 2u64.to_noun(),
+        });
+        let merged = NoteData::merge_for_output(&[&earlier, &last]);
+        assert_eq!(merged.hash(), last.note_data.hash());
+
+        assert!(NoteData::merge_for_output(&[]).entries.is_empty());
+    }
+}
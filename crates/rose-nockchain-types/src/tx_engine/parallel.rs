@@ -0,0 +1,74 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use rayon::prelude::*;
+
+use super::note::{Name, Nicks, Note};
+use super::tx::{AcceptanceError, RawTx};
+
+/// Fully verify a transaction: structure, balance and fee (see [`RawTx::check_acceptance`]), then
+/// signatures (see [`RawTx::verify_signatures`]).
+pub fn verify_transaction(
+    tx: &RawTx,
+    notes: &BTreeMap<Name, Note>,
+    fee_per_word: Nicks,
+) -> Result<(), AcceptanceError> {
+    tx.check_acceptance(notes, fee_per_word)?;
+    tx.verify_signatures()
+}
+
+/// Verify many transactions across threads, returning results in the order of `txs`.
+pub fn verify_transactions_parallel(
+    txs: &[(RawTx, BTreeMap<Name, Note>)],
+    fee_per_word: Nicks,
+) -> Vec<Result<(), AcceptanceError>> {
+    txs.par_iter()
+        .map(|(tx, notes)| verify_transaction(tx, notes, fee_per_word))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx_engine::tx::tests::acceptance_fixture;
+    use crate::Spend;
+    use alloc::vec;
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let (tx, notes, fee_per_word) = acceptance_fixture();
+        let good = (tx, notes);
+
+        let mut unbalanced = good.clone();
+        unbalanced.1.values_mut().for_each(|note| note.assets += 1);
+
+        let mut bad_sig = good.clone();
+        let Spend::Witness(ws) = &mut bad_sig.0.spends.0[0].1 else {
+            panic!("expected witness spend");
+        };
+        ws.witness.pkh_signature.0[0].2.s += 1u64;
+
+        let mut missing_note = good.clone();
+        missing_note.1.clear();
+
+        let txs = vec![good, unbalanced, bad_sig, missing_note];
+        let results = verify_transactions_parallel(&txs, fee_per_word);
+
+        assert_eq!(results.len(), txs.len());
+        for ((tx, notes), res) in txs.iter().zip(&results) {
+            let sequential = tx
+                .check_acceptance(notes, fee_per_word)
+                .and_then(|_| tx.verify_signatures());
+            assert_eq!(*res, sequential);
+        }
+        assert_eq!(results[0], Ok(()));
+        assert!(matches!(
+            results[1],
+            Err(AcceptanceError::Unbalanced { .. })
+        ));
+        assert!(matches!(
+            results[2],
+            Err(AcceptanceError::InvalidSignature(_))
+        ));
+        assert!(matches!(results[3], Err(AcceptanceError::NoteNotFound(_))));
+    }
+}
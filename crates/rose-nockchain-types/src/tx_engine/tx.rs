@@ -1,4 +1,4 @@
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
@@ -6,8 +6,9 @@ use rose_crypto::{PrivateKey, PublicKey, Signature};
 use rose_ztd::{Digest, Hashable as HashableTrait, Noun, NounDecode, NounEncode, ZMap, ZSet};
 use rose_ztd_derive::{Hashable, NounDecode, NounEncode};
 
-use super::note::{Name, Note, NoteData, Source, TimelockRange, Version};
+use super::note::{BlockHeight, Name, Note, NoteData, Source, TimelockRange, Version};
 use crate::{Nicks, Pkh};
+use serde::{Deserialize, Serialize};
 
 fn noun_words(n: &Noun) -> u64 {
     match n {
@@ -99,6 +100,38 @@ impl Seed {
     pub fn note_data_words(&self) -> u64 {
         noun_words(&self.note_data.to_noun())
     }
+
+    /// Name the tx engine assigns to this seed's output, assuming no other seed of the
+    /// transaction shares its lock root.
+    pub fn expected_output_name(&self) -> Name {
+        output_name(self.lock_root.hash(), [self])
+    }
+
+    /// Name of the output merged from `seeds`, i.e. all seeds of a transaction sharing a lock
+    /// root. Returns `None` if `seeds` is empty, or the lock roots differ.
+    pub fn merged_output_name(seeds: &[&Seed]) -> Option<Name> {
+        let lock_root = seeds.first()?.lock_root.hash();
+        if seeds.iter().any(|s| s.lock_root.hash() != lock_root) {
+            return None;
+        }
+        Some(output_name(lock_root, seeds.iter().copied()))
+    }
+}
+
+/// The output's source commits to its seeds, with the output source omitted.
+fn output_name<'a>(lock_root: Digest, seeds: impl IntoIterator<Item = &'a Seed>) -> Name {
+    let mut normalized_seeds_set: ZSet<Seed> = ZSet::new();
+    for seed in seeds {
+        let mut normalized_seed = seed.clone();
+        normalized_seed.output_source = None;
+        normalized_seeds_set.insert(normalized_seed);
+    }
+
+    let src = Source {
+        hash: normalized_seeds_set.hash(),
+        is_coinbase: false,
+    };
+    Name::new_v1(lock_root, src)
 }
 
 impl HashableTrait for Seed {
@@ -144,6 +177,24 @@ impl Seeds {
     pub fn sig_hash(&self) -> Digest {
         ZSet::from_iter(self.0.iter().map(SigHashSeed)).hash()
     }
+
+    /// Add a seed, merging it into an existing seed with the same lock root, if any.
+    ///
+    /// Merged seeds have their gifts summed, the same way the tx engine merges seeds into one
+    /// output note per lock root. Note-data is that of the new seed, with the memo of the existing
+    /// seed carried over if the new one has none (see [`NoteData::merge_for_output`]). Order of
+    /// seeds is otherwise preserved.
+    pub fn insert_or_merge(&mut self, seed: Seed) -> &mut Self {
+        let lock_root = seed.lock_root.hash();
+        let Some(existing) = self.0.iter_mut().find(|v| v.lock_root.hash() == lock_root) else {
+            self.0.push(seed);
+            return self;
+        };
+
+        existing.gift += seed.gift;
+        existing.note_data = NoteData::merge_for_output(&[&*existing, &seed]);
+        self
+    }
 }
 
 impl HashableTrait for Seeds {
@@ -385,12 +436,64 @@ impl Spend {
         }
     }
 
+    /// Check that all attached signatures are valid over this spend's sig hash.
+    ///
+    /// This does not check whether the signatures satisfy the spend condition.
+    pub fn verify_signatures(&self) -> bool {
+        let sig_hash = self.sig_hash();
+        match self {
+            Spend::Legacy(s) => s
+                .signature
+                .0
+                .iter()
+                .all(|(pk, sig)| pk.verify(&sig_hash, sig)),
+            Spend::Witness(s) => s.witness.pkh_signature.verify_all(&sig_hash).is_ok(),
+        }
+    }
+
     pub fn clear_signatures(&mut self) {
         match self {
             Spend::Legacy(s) => s.signature.clear(),
             Spend::Witness(s) => s.witness.pkh_signature.0.clear(),
         }
     }
+
+    /// Public keys and signatures attached to this spend.
+    pub fn signatures(&self) -> Vec<(PublicKey, Signature)> {
+        match self {
+            Spend::Legacy(s) => s.signature.0.clone(),
+            Spend::Witness(s) => s
+                .witness
+                .pkh_signature
+                .0
+                .iter()
+                .map(|(_, pk, sig)| (pk.clone(), sig.clone()))
+                .collect(),
+        }
+    }
+
+    /// Hashes of the public keys that signed this spend.
+    pub fn signer_hashes(&self) -> Vec<Digest> {
+        match self {
+            Spend::Legacy(s) => s.signature.signer_hashes().collect(),
+            Spend::Witness(s) => s
+                .witness
+                .pkh_signature
+                .0
+                .iter()
+                .map(|(pkh, _, _)| *pkh)
+                .collect(),
+        }
+    }
+
+    fn without_unlocks(&self) -> Spend {
+        let mut spend = self.clone();
+        spend.clear_signatures();
+        if let Spend::Witness(s) = &mut spend {
+            s.witness.hax_map = ZMap::new();
+        }
+        spend
+    }
 }
 
 impl HashableTrait for Spend {
@@ -431,6 +534,40 @@ impl NounEncode for PkhSignature {
     }
 }
 
+impl PkhSignature {
+    /// Check that every entry's public key hashes to its stated pkh, and that its signature is
+    /// valid over `sig_hash`.
+    pub fn verify_all(&self, sig_hash: &Digest) -> Result<(), SigError> {
+        for (pkh, pk, sig) in &self.0 {
+            if pk.hash() != *pkh {
+                return Err(SigError::PkhMismatch(*pkh));
+            }
+            if !pk.verify(sig_hash, sig) {
+                return Err(SigError::InvalidSignature(*pkh));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reason a signature entry was rejected in [`PkhSignature::verify_all`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigError {
+    PkhMismatch(Digest),
+    InvalidSignature(Digest),
+}
+
+impl core::fmt::Display for SigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SigError::PkhMismatch(pkh) => {
+                write!(f, "Public key does not hash to the stated pkh {pkh}")
+            }
+            SigError::InvalidSignature(pkh) => write!(f, "Invalid signature from pkh {pkh}"),
+        }
+    }
+}
+
 impl NounDecode for PkhSignature {
     fn from_noun(noun: &Noun) -> Option<Self> {
         Some(Self(
@@ -447,6 +584,8 @@ pub struct Witness {
     pub lock_merkle_proof: LockMerkleProof,
     pub pkh_signature: PkhSignature,
     pub hax_map: ZMap<Digest, Noun>,
+    /// Reserved by the tx engine, always `~`. Timelocks are checked against the height of the
+    /// block including the transaction, so the witness asserts nothing about heights.
     pub tim: (),
 }
 
@@ -494,6 +633,15 @@ impl HashableTrait for LockMerkleProof {
     }
 }
 
+impl LockMerkleProof {
+    /// Whether this proof is valid for its spend condition, and the condition is the lock of the
+    /// note `name`.
+    pub fn commits_to(&self, name: &Name) -> bool {
+        self.proof.root == self.spend_condition.hash()
+            && name.first == (true, self.proof.root).hash()
+    }
+}
+
 #[derive(Debug, Clone, NounEncode, NounDecode, Hashable)]
 pub struct MerkleProof {
     pub root: Digest,
@@ -545,6 +693,120 @@ impl SpendCondition {
     pub fn brn(&self) -> bool {
         self.0.iter().any(|v| matches!(v, LockPrimitive::Brn))
     }
+
+    /// The pkh, if this is the common single-signature lock: a lone 1-of-1 pkh primitive.
+    pub fn is_single_pkh(&self) -> Option<Digest> {
+        match &self.0[..] {
+            [LockPrimitive::Pkh(pkh)] if pkh.m == 1 && pkh.hashes.len() == 1 => Some(pkh.hashes[0]),
+            _ => None,
+        }
+    }
+
+    /// Check that the condition is well-formed: it has at least one primitive, pkh thresholds
+    /// are satisfiable, timelock ranges are not inverted, and hashlocks commit to at least one
+    /// preimage.
+    pub fn validate(&self) -> Result<(), LockError> {
+        if self.0.is_empty() {
+            return Err(LockError::Empty);
+        }
+        for p in &self.0 {
+            match p {
+                LockPrimitive::Pkh(pkh) => {
+                    let n = pkh.hashes.len() as u64;
+                    if pkh.m == 0 || pkh.m > n {
+                        return Err(LockError::InvalidThreshold { m: pkh.m, n });
+                    }
+                }
+                LockPrimitive::Tim(tim) => {
+                    tim.rel.validate()?;
+                    tim.abs.validate()?;
+                }
+                LockPrimitive::Hax(hax) if hax.0.is_empty() => {
+                    return Err(LockError::EmptyHashlock);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode a spend condition from an untrusted noun, and [`validate`](Self::validate) it.
+    pub fn try_from_noun(noun: &Noun) -> Result<Self, LockError> {
+        let spend_condition = Self::from_noun(noun).ok_or(LockError::Decode)?;
+        spend_condition.validate()?;
+        Ok(spend_condition)
+    }
+
+    /// Describe the requirements of this condition, one clause per lock primitive.
+    pub fn describe(&self) -> Vec<LockClause> {
+        self.0
+            .iter()
+            .map(|p| match p {
+                LockPrimitive::Pkh(pkh) => LockClause::Multisig {
+                    m: pkh.m,
+                    n: pkh.hashes.len() as u64,
+                },
+                LockPrimitive::Tim(tim) => LockClause::Timelock {
+                    rel_min: tim.rel.min,
+                    rel_max: tim.rel.max,
+                    abs_min: tim.abs.min,
+                    abs_max: tim.abs.max,
+                },
+                LockPrimitive::Hax(hax) => LockClause::Hashlock {
+                    count: hax.0.len() as u64,
+                },
+                LockPrimitive::Brn => LockClause::Burn,
+            })
+            .collect()
+    }
+}
+
+/// Reason a spend condition was rejected in [`SpendCondition::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockError {
+    Decode,
+    Empty,
+    InvalidThreshold { m: u64, n: u64 },
+    EmptyHashlock,
+    InvertedTimelock { min: BlockHeight, max: BlockHeight },
+}
+
+impl core::fmt::Display for LockError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LockError::Decode => write!(f, "Unable to decode spend condition"),
+            LockError::Empty => write!(f, "Spend condition has no lock primitives"),
+            LockError::InvalidThreshold { m, n } => {
+                write!(f, "Invalid pkh threshold: {m} of {n}")
+            }
+            LockError::EmptyHashlock => write!(f, "Hashlock has no preimage hashes"),
+            LockError::InvertedTimelock { min, max } => {
+                write!(f, "Timelock range is unsatisfiable: min {min} > max {max}")
+            }
+        }
+    }
+}
+
+/// Human-readable summary of a single lock primitive, see [`SpendCondition::describe`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LockClause {
+    /// `m` signatures required out of `n` allowed signers
+    Multisig { m: u64, n: u64 },
+    Timelock {
+        rel_min: Option<BlockHeight>,
+        rel_max: Option<BlockHeight>,
+        abs_min: Option<BlockHeight>,
+        abs_max: Option<BlockHeight>,
+    },
+    /// Number of preimages that need to be revealed
+    Hashlock { count: u64 },
+    /// Unspendable
+    Burn,
 }
 
 #[derive(Debug, Clone)]
@@ -590,24 +852,66 @@ impl HashableTrait for LockPrimitive {
     }
 }
 
-#[derive(Debug, Clone, NounEncode, Hashable, NounDecode)]
+impl LockPrimitive {
+    /// Rank of the primitive kind in the canonical order: `Pkh < Tim < Hax < Brn`.
+    fn rank(&self) -> u8 {
+        match self {
+            LockPrimitive::Pkh(_) => 0,
+            LockPrimitive::Tim(_) => 1,
+            LockPrimitive::Hax(_) => 2,
+            LockPrimitive::Brn => 3,
+        }
+    }
+}
+
+/// Primitives are equal whenever their hashes are equal.
+impl PartialEq for LockPrimitive {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash() == other.hash()
+    }
+}
+
+impl Eq for LockPrimitive {}
+
+impl PartialOrd for LockPrimitive {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Canonical order of primitives: by kind (`Pkh < Tim < Hax < Brn`), then by hash.
+impl Ord for LockPrimitive {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| self.hash().cmp(&other.hash()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, NounEncode, Hashable, NounDecode)]
 pub struct LockTim {
     pub rel: TimelockRange,
     pub abs: TimelockRange,
 }
 
 impl LockTim {
+    /// Build a timelock, rejecting relative or absolute ranges with `min > max`.
+    pub fn try_new(rel: TimelockRange, abs: TimelockRange) -> Result<Self, LockError> {
+        rel.validate()?;
+        abs.validate()?;
+        Ok(Self { rel, abs })
+    }
+
     pub fn coinbase() -> Self {
-        Self {
-            rel: TimelockRange {
-                min: Some(100),
-                max: None,
-            },
-            abs: TimelockRange::none(),
-        }
+        crate::constants::COINBASE_LOCK_TIM
     }
 }
 
+/// Hashlock, committing to a set of preimage hashes.
+///
+/// The tx engine requires a preimage for every hash in the set (n-of-n). There is no threshold
+/// in the lock's noun, so a k-of-n hashlock can only be expressed as alternative spend
+/// conditions, one per subset of `k` hashes, in the note's lock tree.
 #[derive(Debug, Clone)]
 pub struct Hax(pub Vec<Digest>);
 
@@ -636,10 +940,17 @@ pub type TxId = Digest;
 pub struct Spends(pub Vec<(Name, Spend)>);
 
 impl Spends {
+    /// Fee required for these spends at `per_word`. For the fee actually paid, see
+    /// [`Spends::total_fee`].
     pub fn fee(&self, per_word: Nicks) -> Nicks {
         Spend::fee_for_many(self.0.iter().map(|v| &v.1), per_word)
     }
 
+    /// Sum of the fees set on the spends.
+    pub fn total_fee(&self) -> Nicks {
+        self.0.iter().map(|(_, s)| s.fee()).sum()
+    }
+
     pub fn split_witness(&self) -> (Spends, WitnessData) {
         let mut spends = Spends(Vec::new());
         let mut witness_data = WitnessData::default();
@@ -717,9 +1028,28 @@ impl RawTx {
         }
     }
 
+    /// Names of the notes spent by this transaction.
+    pub fn input_names(&self) -> Vec<Name> {
+        self.spends.0.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Lock roots of the outputs, i.e. one entry per output note, without building the notes.
+    pub fn distinct_output_locks(&self) -> Vec<Digest> {
+        self.spends
+            .0
+            .iter()
+            .flat_map(|(_, spend)| spend.seeds().0.iter())
+            .map(|seed| seed.lock_root.hash())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
     /// Calculate output notes from the transaction spends.
     ///
     /// This function combines seeds across multiple spends into one output note per-lock-root.
+    /// Outputs are ordered by lock root hash, as seeds are committed to as z-sets: the order is
+    /// determined by content, and cannot be chosen (e.g. shuffled) by the builder.
     pub fn outputs(&self) -> Vec<Note> {
         // We must convert to ZMap to preserve the order of the spends.
         let spends = ZMap::from_iter(self.spends.0.iter().cloned());
@@ -745,41 +1075,9 @@ impl RawTx {
 
             let total_assets: Nicks = seeds.iter().map(|s| s.gift).sum();
 
-            // Hoon code ends up taking the last note-data for the output note, by the tap order of z-set.
-            //
-            // However, memo placement is tricky: memo note-data might live on a different seed than
-            // the last one (because adding memo changes seed ordering). To avoid silently dropping
-            // memo in wallet UIs, preserve the memo entry if any seed for this lock-root has it.
-            let mut note_data = seeds[seeds.len() - 1].note_data.clone();
-            let has_memo = note_data.entries.iter().any(|e| e.key == crate::MEMO_KEY);
-            if !has_memo {
-                // Prefer the last (highest in z-set order) seed that has memo, if any.
-                if let Some(memo_val) = seeds.iter().rev().find_map(|s| {
-                    s.note_data
-                        .entries
-                        .iter()
-                        .find(|e| e.key == crate::MEMO_KEY)
-                        .map(|e| e.val.clone())
-                }) {
-                    note_data.push_memo(memo_val);
-                }
-            }
-
-            let mut normalized_seeds_set: ZSet<Seed> = ZSet::new();
-            for seed in seeds {
-                let mut normalized_seed = seed.clone();
-                normalized_seed.output_source = None;
-                normalized_seeds_set.insert(normalized_seed);
-            }
-
-            let src_hash = normalized_seeds_set.hash();
-
-            let src = Source {
-                hash: src_hash,
-                is_coinbase: false,
-            };
+            let note_data = NoteData::merge_for_output(&seeds.iter().collect::<Vec<_>>());
 
-            let name = Name::new_v1(lock_root_hash, src);
+            let name = output_name(lock_root_hash, &seeds);
 
             let note = Note::new(
                 Version::V1,
@@ -798,13 +1096,13 @@ impl RawTx {
 
     pub fn to_nockchain_tx(&self) -> NockchainTx {
         let (spends, witness_data) = self.spends.split_witness();
-        NockchainTx {
-            version: self.version.clone(),
-            id: self.id,
+        NockchainTx::new(
+            self.version.clone(),
+            self.id,
             spends,
-            display: TransactionDisplay::default(),
+            TransactionDisplay::default(),
             witness_data,
-        }
+        )
     }
 
     /// Sign all spends in this raw transaction with the given key.
@@ -824,6 +1122,280 @@ impl RawTx {
     pub fn calc_id(&self) -> TxId {
         (&1, &self.spends).hash()
     }
+
+    /// Check whether this transaction is acceptable for relaying, given its input notes.
+    ///
+    /// This verifies the structure of the transaction (unique inputs, spend format matching the
+    /// note version, merkle proofs committing to the note's lock), that every spend is balanced
+    /// (`assets == gifts + fee`), and that the total fee covers the minimum for `fee_per_word`.
+    ///
+    /// Unlocks (signatures, preimages) are not verified here.
+    pub fn check_acceptance(
+        &self,
+        notes: &BTreeMap<Name, Note>,
+        fee_per_word: Nicks,
+    ) -> Result<(), AcceptanceError> {
+        self.check_spend_structure()?;
+
+        for (name, spend) in &self.spends.0 {
+            let note = notes
+                .get(name)
+                .ok_or_else(|| AcceptanceError::NoteNotFound(name.clone()))?;
+
+            match spend {
+                Spend::Legacy(_) if note.version != Version::V0 => {
+                    return Err(AcceptanceError::SpendVersionMismatch(name.clone()));
+                }
+                Spend::Witness(_) if note.version == Version::V0 => {
+                    return Err(AcceptanceError::SpendVersionMismatch(name.clone()));
+                }
+                _ => {}
+            }
+
+            let spent = spend
+                .seeds()
+                .0
+                .iter()
+                .map(|s| s.gift)
+                .fold(spend.fee(), Nicks::saturating_add);
+            if spent != note.assets {
+                return Err(AcceptanceError::Unbalanced {
+                    name: name.clone(),
+                    assets: note.assets,
+                    spent,
+                });
+            }
+        }
+
+        let needed_fee = self.spends.fee(fee_per_word);
+        let fee = self.spends.total_fee();
+        if fee < needed_fee {
+            return Err(AcceptanceError::InsufficientFee(needed_fee, fee));
+        }
+
+        Ok(())
+    }
+
+    /// Check the parts of the transaction that do not depend on the input notes.
+    ///
+    /// This verifies that there is at least one spend, inputs are unique, merkle proofs of witness
+    /// spends commit to the spent note's lock, and the transaction id matches its contents.
+    pub fn validate_structure(&self) -> Result<(), AcceptanceError> {
+        self.check_spend_structure()?;
+
+        if self.id != (&self.version, &self.spends).hash() {
+            return Err(AcceptanceError::IdMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Spend checks shared by [`Self::check_acceptance`] and [`Self::validate_structure`]: at
+    /// least one spend, unique inputs, and witness merkle proofs committing to the note's lock.
+    fn check_spend_structure(&self) -> Result<(), AcceptanceError> {
+        if self.spends.0.is_empty() {
+            return Err(AcceptanceError::NoSpends);
+        }
+
+        let mut seen = BTreeSet::new();
+        for (name, spend) in &self.spends.0 {
+            if !seen.insert(name) {
+                return Err(AcceptanceError::DuplicateInput(name.clone()));
+            }
+            if let Spend::Witness(ws) = spend {
+                if !ws.witness.lock_merkle_proof.commits_to(name) {
+                    return Err(AcceptanceError::InvalidMerkleProof(name.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify signatures of all spends, see [`Spend::verify_signatures`].
+    pub fn verify_signatures(&self) -> Result<(), AcceptanceError> {
+        for (name, spend) in &self.spends.0 {
+            if !spend.verify_signatures() {
+                return Err(AcceptanceError::InvalidSignature(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare against a returned copy of this transaction, which may only differ in unlocks.
+    ///
+    /// Errors if `other` spends different inputs, or if any spend differs once signatures and
+    /// preimages are stripped (seeds, fees, spend conditions). Otherwise, reports the signatures
+    /// and preimages that `other` has on top of `self`.
+    pub fn witness_diff(&self, other: &RawTx) -> Result<WitnessDiff, TxMismatch> {
+        let ours: BTreeMap<&Name, &Spend> = self.spends.0.iter().map(|(n, s)| (n, s)).collect();
+        let theirs: BTreeMap<&Name, &Spend> = other.spends.0.iter().map(|(n, s)| (n, s)).collect();
+        if ours.len() != self.spends.0.len()
+            || theirs.len() != other.spends.0.len()
+            || !ours.keys().eq(theirs.keys())
+        {
+            return Err(TxMismatch::InputsDiffer);
+        }
+
+        let mut diff = WitnessDiff::default();
+        for ((name, ours), theirs) in ours.into_iter().zip(theirs.into_values()) {
+            if ours.without_unlocks().hash() != theirs.without_unlocks().hash() {
+                return Err(TxMismatch::SpendDiffers(name.clone()));
+            }
+
+            let signers: BTreeSet<Digest> = ours.signer_hashes().into_iter().collect();
+            diff.added_signatures.extend(
+                theirs
+                    .signer_hashes()
+                    .into_iter()
+                    .filter(|pkh| !signers.contains(pkh))
+                    .map(|pkh| (name.clone(), pkh)),
+            );
+
+            if let (Spend::Witness(ours), Spend::Witness(theirs)) = (ours, theirs) {
+                diff.added_preimages.extend(
+                    theirs
+                        .witness
+                        .hax_map
+                        .clone()
+                        .into_iter()
+                        .filter(|(digest, _)| ours.witness.hax_map.get(digest).is_none())
+                        .map(|(digest, _)| (name.clone(), digest)),
+                );
+            }
+        }
+        Ok(diff)
+    }
+}
+
+/// Unlocks added by a returned transaction, see [`RawTx::witness_diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitnessDiff {
+    /// Signer pubkey hashes, per spend.
+    pub added_signatures: Vec<(Name, Digest)>,
+    /// Preimage hashes, per spend.
+    pub added_preimages: Vec<(Name, Digest)>,
+}
+
+impl WitnessDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_signatures.is_empty() && self.added_preimages.is_empty()
+    }
+}
+
+/// Reason a returned transaction was rejected in [`RawTx::witness_diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxMismatch {
+    InputsDiffer,
+    SpendDiffers(Name),
+}
+
+impl core::fmt::Display for TxMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TxMismatch::InputsDiffer => write!(f, "Transaction spends different inputs"),
+            TxMismatch::SpendDiffers(name) => write!(
+                f,
+                "Spend of note [{} {}] differs beyond witness data",
+                name.first, name.last
+            ),
+        }
+    }
+}
+
+/// Reason for rejecting a transaction in [`RawTx::check_acceptance`]
+///
+/// Each variant has a stable, machine-readable [`code`](AcceptanceError::code):
+///
+/// | Variant                | Code                |
+/// |------------------------|---------------------|
+/// | `NoSpends`             | `NO_SPENDS`         |
+/// | `DuplicateInput`       | `DUPLICATE_INPUT`   |
+/// | `NoteNotFound`         | `NOTE_NOT_FOUND`    |
+/// | `SpendVersionMismatch` | `VERSION_MISMATCH`  |
+/// | `InvalidMerkleProof`   | `MERKLE_MISMATCH`   |
+/// | `IdMismatch`           | `ID_MISMATCH`       |
+/// | `Unbalanced`           | `UNBALANCED`        |
+/// | `InsufficientFee`      | `FEE_TOO_LOW`       |
+/// | `InvalidSignature`     | `INVALID_SIGNATURE` |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceptanceError {
+    NoSpends,
+    DuplicateInput(Name),
+    NoteNotFound(Name),
+    SpendVersionMismatch(Name),
+    InvalidMerkleProof(Name),
+    IdMismatch,
+    Unbalanced {
+        name: Name,
+        assets: Nicks,
+        spent: Nicks,
+    },
+    InsufficientFee(Nicks, Nicks),
+    InvalidSignature(Name),
+}
+
+impl AcceptanceError {
+    /// Stable code of the error kind, e.g. to map to API status codes. Never changes for a
+    /// variant, unlike the `Display` message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AcceptanceError::NoSpends => "NO_SPENDS",
+            AcceptanceError::DuplicateInput(_) => "DUPLICATE_INPUT",
+            AcceptanceError::NoteNotFound(_) => "NOTE_NOT_FOUND",
+            AcceptanceError::SpendVersionMismatch(_) => "VERSION_MISMATCH",
+            AcceptanceError::InvalidMerkleProof(_) => "MERKLE_MISMATCH",
+            AcceptanceError::IdMismatch => "ID_MISMATCH",
+            AcceptanceError::Unbalanced { .. } => "UNBALANCED",
+            AcceptanceError::InsufficientFee(_, _) => "FEE_TOO_LOW",
+            AcceptanceError::InvalidSignature(_) => "INVALID_SIGNATURE",
+        }
+    }
+}
+
+impl core::fmt::Display for AcceptanceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AcceptanceError::NoSpends => write!(f, "Transaction has no spends"),
+            AcceptanceError::DuplicateInput(name) => {
+                write!(f, "Note [{} {}] is spent twice", name.first, name.last)
+            }
+            AcceptanceError::NoteNotFound(name) => {
+                write!(f, "Unable to find note [{} {}]", name.first, name.last)
+            }
+            AcceptanceError::SpendVersionMismatch(name) => write!(
+                f,
+                "Spend format does not match version of note [{} {}]",
+                name.first, name.last
+            ),
+            AcceptanceError::InvalidMerkleProof(name) => write!(
+                f,
+                "Lock merkle proof does not match note [{} {}]",
+                name.first, name.last
+            ),
+            AcceptanceError::IdMismatch => {
+                write!(f, "Transaction id does not match its contents")
+            }
+            AcceptanceError::Unbalanced {
+                name,
+                assets,
+                spent,
+            } => write!(
+                f,
+                "Spend of note [{} {}] is not balanced (assets: {assets}, gifts + fee: {spent})",
+                name.first, name.last
+            ),
+            AcceptanceError::InsufficientFee(expected, got) => write!(
+                f,
+                "Insufficient fee for transaction (needed: {expected}, got: {got})"
+            ),
+            AcceptanceError::InvalidSignature(name) => write!(
+                f,
+                "Invalid signature on spend of note [{} {}]",
+                name.first, name.last
+            ),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -833,9 +1405,61 @@ pub struct NockchainTx {
     pub spends: Spends,
     pub display: TransactionDisplay,
     pub witness_data: WitnessData,
+    outputs_cache: OutputsCache,
+}
+
+/// Output notes of a [`NockchainTx`], along with the noun of the spends and witness data they
+/// were computed from, so that changes to either are noticed.
+type CachedOutputs = Option<(Noun, Vec<Note>)>;
+
+#[derive(Debug, Default)]
+struct OutputsCache {
+    #[cfg(feature = "std")]
+    entry: std::sync::Mutex<CachedOutputs>,
+    #[cfg(not(feature = "std"))]
+    entry: core::cell::RefCell<CachedOutputs>,
+}
+
+impl OutputsCache {
+    fn with<R>(&self, f: impl FnOnce(&mut CachedOutputs) -> R) -> R {
+        #[cfg(feature = "std")]
+        let mut entry = self
+            .entry
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        #[cfg(not(feature = "std"))]
+        let mut entry = self.entry.borrow_mut();
+        f(&mut entry)
+    }
+}
+
+impl Clone for OutputsCache {
+    fn clone(&self) -> Self {
+        let entry = self.with(|entry| entry.clone());
+        Self {
+            entry: entry.into(),
+        }
+    }
 }
 
 impl NockchainTx {
+    pub fn new(
+        version: Version,
+        id: TxId,
+        spends: Spends,
+        display: TransactionDisplay,
+        witness_data: WitnessData,
+    ) -> Self {
+        Self {
+            version,
+            id,
+            spends,
+            display,
+            witness_data,
+            outputs_cache: OutputsCache::default(),
+        }
+    }
+
     pub fn to_raw_tx(&self) -> RawTx {
         let spends = self.spends.apply_witness(&self.witness_data);
 
@@ -846,11 +1470,116 @@ impl NockchainTx {
         }
     }
 
+    /// Output notes of this transaction.
+    ///
+    /// These are computed on the first call and cached until the spends or witness data change.
     pub fn outputs(&self) -> Vec<Note> {
-        self.to_raw_tx().outputs()
+        let key = (&self.spends, &self.witness_data).to_noun();
+        self.outputs_cache.with(|entry| match entry {
+            Some((cached_key, notes)) if *cached_key == key => notes.clone(),
+            _ => {
+                let notes = self.to_raw_tx().outputs();
+                *entry = Some((key, notes.clone()));
+                notes
+            }
+        })
+    }
+
+    /// Fee paid by this transaction, as set on its spends.
+    pub fn total_fee(&self) -> Nicks {
+        self.spends.total_fee()
+    }
+
+    /// Whether both transactions have the same consensus data, ignoring `display`.
+    ///
+    /// Transactions fetched from the network have an empty display, so use this to compare them
+    /// against locally built ones.
+    pub fn canonical_eq(&self, other: &NockchainTx) -> bool {
+        self.version == other.version
+            && self.id == other.id
+            && self.spends.to_noun() == other.spends.to_noun()
+            && self.witness_data.to_noun() == other.witness_data.to_noun()
+    }
+
+    /// Self-contained entry for a wallet's local transaction history, see [`TxHistoryRecord`].
+    ///
+    /// `timestamp` is stored as given, e.g. the time the wallet broadcast the transaction.
+    pub fn history_record(&self, timestamp: u64) -> TxHistoryRecord {
+        let raw = self.to_raw_tx();
+        // Output names commit to their lock root, see `Name::new_v1`.
+        let lock_roots: BTreeMap<Digest, Digest> = raw
+            .distinct_output_locks()
+            .into_iter()
+            .map(|lock_root| ((true, lock_root).hash(), lock_root))
+            .collect();
+        let outputs = raw
+            .outputs()
+            .into_iter()
+            .map(|note| {
+                let lock = lock_roots
+                    .get(&note.name.first)
+                    .and_then(|lock_root| self.display.outputs.get(lock_root));
+                let recipient = lock
+                    .and_then(|meta| meta.lock.is_single_pkh())
+                    .map(|pkh| pkh.to_string());
+                TxHistoryOutput {
+                    name: note.name.into(),
+                    recipient,
+                    amount: note.assets.to_string(),
+                }
+            })
+            .collect();
+
+        TxHistoryRecord {
+            id: self.id.to_string(),
+            fee: self.total_fee().to_string(),
+            timestamp,
+            inputs: self
+                .spends
+                .0
+                .iter()
+                .map(|(name, _)| name.clone().into())
+                .collect(),
+            outputs,
+        }
+    }
+}
+
+/// History entry of a transaction, see [`NockchainTx::history_record`].
+///
+/// Digests are base58 and amounts are strings, so the record is safe to store as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxHistoryRecord {
+    pub id: String,
+    pub fee: String,
+    pub timestamp: u64,
+    pub inputs: Vec<TxHistoryName>,
+    pub outputs: Vec<TxHistoryOutput>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxHistoryName {
+    pub first: String,
+    pub last: String,
+}
+
+impl From<Name> for TxHistoryName {
+    fn from(name: Name) -> Self {
+        Self {
+            first: name.first.to_string(),
+            last: name.last.to_string(),
+        }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxHistoryOutput {
+    pub name: TxHistoryName,
+    /// Address paid, if the output's lock is a known single pkh
+    pub recipient: Option<String>,
+    pub amount: String,
+}
+
 impl NounEncode for NockchainTx {
     fn to_noun(&self) -> Noun {
         (
@@ -868,13 +1597,7 @@ impl NounDecode for NockchainTx {
     fn from_noun(noun: &Noun) -> Option<Self> {
         let (version, id, spends, display, witness_data): (Version, _, _, _, _) =
             NounDecode::from_noun(noun)?;
-        Some(Self {
-            version,
-            id,
-            spends,
-            display,
-            witness_data,
-        })
+        Some(Self::new(version, id, spends, display, witness_data))
     }
 }
 
@@ -933,7 +1656,7 @@ impl NounDecode for TransactionDisplay {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use alloc::vec;
     use bip39::Mnemonic;
@@ -1200,4 +1923,490 @@ mod tests {
             "outputs must preserve memo even if memo seed is not last in z-set order"
         );
     }
+
+    #[test]
+    fn test_seeds_insert_or_merge() {
+        let lock_a = SpendCondition::new_pkh(Pkh::single(1u64.hash()));
+        let lock_b = SpendCondition::new_pkh(Pkh::single(2u64.hash()));
+        let seed = |lock: &SpendCondition, gift| Seed {
+            output_source: None,
+            lock_root: LockRoot::Lock(lock.clone()),
+            note_data: NoteData::empty(),
+            gift,
+            parent_hash: 3u64.hash(),
+        };
+
+        let mut seeds = Seeds(vec![]);
+        seeds
+            .insert_or_merge(seed(&lock_a, 100))
+            .insert_or_merge(seed(&lock_b, 10));
+        let mut with_memo = seed(&lock_a, 200);
+        with_memo.note_data.push_memo_utf8("hi");
+        seeds.insert_or_merge(with_memo);
+
+        assert_eq!(seeds.0.len(), 2);
+        assert_eq!(seeds.0[0].lock_root.hash(), lock_a.hash());
+        assert_eq!(seeds.0[0].gift, 300);
+        assert!(seeds.0[0]
+            .note_data
+            .entries
+            .iter()
+            .any(|e| e.key == crate::MEMO_KEY));
+        assert_eq!(seeds.0[1].lock_root.hash(), lock_b.hash());
+        assert_eq!(seeds.0[1].gift, 10);
+
+        // The new seed's note-data wins, keeping the earlier memo.
+        let mut with_lock = seed(&lock_a, 400);
+        with_lock.note_data.push_lock(lock_a.clone());
+        seeds.insert_or_merge(with_lock.clone());
+
+        let mut expected = with_lock.note_data;
+        expected.push_memo_utf8("hi");
+        assert_eq!(seeds.0[0].gift, 700);
+        assert_eq!(seeds.0[0].note_data.hash(), expected.hash());
+        assert!(seeds.0[0].note_data.extract_lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_lock_primitive_order() {
+        let pkh_a = LockPrimitive::Pkh(Pkh::single(1u64.hash()));
+        let pkh_b = LockPrimitive::Pkh(Pkh::single(2u64.hash()));
+        let tim = LockPrimitive::Tim(LockTim::coinbase());
+        let hax = LockPrimitive::Hax(Hax(vec![3u64.hash()]));
+        let brn = LockPrimitive::Brn;
+
+        let mut prims = vec![
+            brn.clone(),
+            hax.clone(),
+            pkh_b.clone(),
+            tim.clone(),
+            pkh_a.clone(),
+        ];
+        prims.sort();
+
+        let (first_pkh, second_pkh) = if pkh_a.hash() < pkh_b.hash() {
+            (&pkh_a, &pkh_b)
+        } else {
+            (&pkh_b, &pkh_a)
+        };
+        assert_eq!(
+            prims,
+            vec![
+                first_pkh.clone(),
+                second_pkh.clone(),
+                tim.clone(),
+                hax.clone(),
+                brn.clone()
+            ]
+        );
+
+        // Sorting again (from any permutation) gives the same order
+        let mut again = prims.clone();
+        again.reverse();
+        again.sort();
+        assert_eq!(again, prims);
+
+        assert_eq!(pkh_a, LockPrimitive::Pkh(Pkh::single(1u64.hash())));
+        assert_ne!(pkh_a, pkh_b);
+    }
+
+    /// A signed one-spend transaction, the note it spends, and the fee rate it was built for.
+    pub(crate) fn acceptance_fixture() -> (RawTx, BTreeMap<Name, Note>, Nicks) {
+        use crate::{LockPrimitive, LockTim, TxBuilder};
+
+        let mnemonic = Mnemonic::parse("dice domain inspire horse time initial monitor nature mass impose tone benefit vibrant dash kiss mosquito rice then color ribbon agent method drop fat").unwrap();
+        let private_key = derive_master_key(&mnemonic.to_seed(""))
+            .private_key
+            .unwrap();
+        let spend_condition = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::single(private_key.public_key().hash())),
+            LockPrimitive::Tim(LockTim::coinbase()),
+        ]);
+        let note = Note::new(
+            Version::V1,
+            13,
+            Name::new(
+                spend_condition.first_name(),
+                "7yMzrJjkb2Xu8uURP7YB3DFcotttR8dKDXF1tSp2wJmmXUvLM7SYzvM"
+                    .try_into()
+                    .unwrap(),
+            ),
+            NoteData::empty(),
+            4294967296,
+        );
+
+        let fee_per_word = 40000;
+        let mut builder = TxBuilder::new(fee_per_word);
+        builder
+            .simple_spend(
+                vec![(note.clone(), spend_condition)],
+                "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+                    .try_into()
+                    .unwrap(),
+                1234567,
+                "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+                    .try_into()
+                    .unwrap(),
+                false,
+                None,
+            )
+            .unwrap()
+            .sign(&private_key);
+        let tx = builder.build().to_raw_tx();
+
+        let notes = BTreeMap::from([(note.name.clone(), note)]);
+        (tx, notes, fee_per_word)
+    }
+
+    #[test]
+    fn test_is_single_pkh() {
+        let pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+        let other: Digest = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+
+        assert_eq!(
+            SpendCondition::new_pkh(Pkh::single(pkh)).is_single_pkh(),
+            Some(pkh)
+        );
+        assert_eq!(
+            SpendCondition::new_pkh(Pkh::new(1, vec![pkh, other])).is_single_pkh(),
+            None
+        );
+        assert_eq!(
+            SpendCondition::new_pkh(Pkh::new(2, vec![pkh, other])).is_single_pkh(),
+            None
+        );
+        assert_eq!(
+            SpendCondition(vec![
+                LockPrimitive::Pkh(Pkh::single(pkh)),
+                LockPrimitive::Tim(LockTim::coinbase()),
+            ])
+            .is_single_pkh(),
+            None
+        );
+        assert_eq!(SpendCondition(vec![]).is_single_pkh(), None);
+    }
+
+    #[test]
+    fn test_spend_condition_try_from_noun() {
+        let pkh: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+        let valid = SpendCondition(vec![
+            LockPrimitive::Pkh(Pkh::new(1, vec![pkh])),
+            LockPrimitive::Hax(Hax(vec![1u64.hash()])),
+        ]);
+        let decoded = SpendCondition::try_from_noun(&valid.to_noun()).unwrap();
+        assert_eq!(decoded.hash(), valid.hash());
+
+        let check = |sc: SpendCondition| SpendCondition::try_from_noun(&sc.to_noun());
+        assert_eq!(check(SpendCondition(vec![])).unwrap_err(), LockError::Empty);
+        assert_eq!(
+            check(SpendCondition::new_pkh(Pkh::new(2, vec![pkh]))).unwrap_err(),
+            LockError::InvalidThreshold { m: 2, n: 1 }
+        );
+        assert_eq!(
+            check(SpendCondition::new_pkh(Pkh::new(0, vec![pkh]))).unwrap_err(),
+            LockError::InvalidThreshold { m: 0, n: 1 }
+        );
+        assert_eq!(
+            check(SpendCondition(vec![LockPrimitive::Hax(Hax(vec![]))])).unwrap_err(),
+            LockError::EmptyHashlock
+        );
+        let inverted = TimelockRange {
+            min: Some(100),
+            max: Some(50),
+        };
+        assert_eq!(
+            check(SpendCondition(vec![LockPrimitive::Tim(LockTim {
+                rel: TimelockRange::none(),
+                abs: inverted.clone(),
+            })]))
+            .unwrap_err(),
+            LockError::InvertedTimelock { min: 100, max: 50 }
+        );
+        assert_eq!(
+            LockTim::try_new(inverted, TimelockRange::none()).unwrap_err(),
+            LockError::InvertedTimelock { min: 100, max: 50 }
+        );
+        assert_eq!(
+            SpendCondition::try_from_noun(&("foo", 0).to_noun()).unwrap_err(),
+            LockError::Decode
+        );
+    }
+
+    #[test]
+    fn test_check_acceptance() {
+        let (tx, notes, fee_per_word) = acceptance_fixture();
+        assert_eq!(tx.check_acceptance(&notes, fee_per_word), Ok(()));
+        assert_eq!(
+            tx.check_acceptance(&BTreeMap::new(), fee_per_word),
+            Err(AcceptanceError::NoteNotFound(tx.spends.0[0].0.clone()))
+        );
+    }
+
+    #[test]
+    fn test_witness_diff() {
+        let (signed, _, _) = acceptance_fixture();
+        let mut unsigned = signed.clone();
+        for (_, spend) in &mut unsigned.spends.0 {
+            spend.clear_signatures();
+        }
+
+        let diff = unsigned.witness_diff(&signed).unwrap();
+        let (name, spend) = &signed.spends.0[0];
+        assert_eq!(
+            diff.added_signatures,
+            vec![(name.clone(), spend.signer_hashes()[0])]
+        );
+        assert!(diff.added_preimages.is_empty());
+        assert!(signed.witness_diff(&signed).unwrap().is_empty());
+
+        let mut tampered = signed.clone();
+        tampered.spends.0[0].1.seeds_mut().0[0].gift += 1;
+        assert_eq!(
+            unsigned.witness_diff(&tampered),
+            Err(TxMismatch::SpendDiffers(name.clone()))
+        );
+
+        let mut fewer = signed.clone();
+        fewer.spends.0.clear();
+        assert_eq!(unsigned.witness_diff(&fewer), Err(TxMismatch::InputsDiffer));
+    }
+
+    #[test]
+    fn test_acceptance_error_codes() {
+        let name = Name::new(1u64.hash(), 2u64.hash());
+        let cases = [
+            (AcceptanceError::NoSpends, "NO_SPENDS"),
+            (
+                AcceptanceError::DuplicateInput(name.clone()),
+                "DUPLICATE_INPUT",
+            ),
+            (
+                AcceptanceError::NoteNotFound(name.clone()),
+                "NOTE_NOT_FOUND",
+            ),
+            (
+                AcceptanceError::SpendVersionMismatch(name.clone()),
+                "VERSION_MISMATCH",
+            ),
+            (
+                AcceptanceError::InvalidMerkleProof(name.clone()),
+                "MERKLE_MISMATCH",
+            ),
+            (AcceptanceError::IdMismatch, "ID_MISMATCH"),
+            (
+                AcceptanceError::Unbalanced {
+                    name: name.clone(),
+                    assets: 1,
+                    spent: 2,
+                },
+                "UNBALANCED",
+            ),
+            (AcceptanceError::InsufficientFee(2, 1), "FEE_TOO_LOW"),
+            (AcceptanceError::InvalidSignature(name), "INVALID_SIGNATURE"),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.code(), code);
+        }
+    }
+
+    #[test]
+    fn test_canonical_eq() {
+        let (tx, _, _) = acceptance_fixture();
+        let local = tx.to_nockchain_tx();
+        let fetched = local.clone();
+        let mut local = local;
+        local.display.inputs.insert(
+            tx.spends.0[0].0.clone(),
+            SpendCondition::new_pkh(Pkh::single(1u64.hash())),
+        );
+        assert_ne!(local.to_noun(), fetched.to_noun());
+        assert!(local.canonical_eq(&fetched));
+
+        let mut unsigned = tx.clone();
+        for (_, spend) in &mut unsigned.spends.0 {
+            spend.clear_signatures();
+        }
+        assert!(!local.canonical_eq(&unsigned.to_nockchain_tx()));
+    }
+
+    #[test]
+    fn test_outputs_cached() {
+        let (tx, _, _) = acceptance_fixture();
+        let expected = tx.outputs();
+        let mut tx = tx.to_nockchain_tx();
+
+        let first = tx.outputs();
+        let second = tx.outputs();
+        assert_eq!(first.hash(), expected.hash());
+        assert_eq!(second.hash(), first.hash());
+
+        // Changing the spends, even on a clone, is reflected in the outputs
+        let mut modified = tx.clone();
+        let Spend::Witness(ws) = &mut modified.spends.0[0].1 else {
+            panic!("expected witness spend");
+        };
+        ws.seeds.0[0].gift -= 1;
+        ws.fee += 1;
+        let outputs = modified.outputs();
+        assert_ne!(outputs.hash(), first.hash());
+        assert_eq!(
+            outputs.iter().map(|n| n.assets).sum::<Nicks>() + 1,
+            first.iter().map(|n| n.assets).sum::<Nicks>()
+        );
+        assert_eq!(tx.outputs().hash(), first.hash());
+
+        tx.spends = modified.spends.clone();
+        assert_eq!(tx.outputs().hash(), outputs.hash());
+    }
+
+    #[test]
+    fn test_history_record() {
+        use alloc::format;
+
+        let (tx, _, _) = acceptance_fixture();
+        let mut tx = tx.to_nockchain_tx();
+        let recipient: Digest = "2nEFkqYm51yfqsYgfRx72w8FF9bmWqnkJu8XqY8T7psXufjYNRxf5ME"
+            .try_into()
+            .unwrap();
+        let lock = SpendCondition::new_pkh(Pkh::single(recipient));
+        tx.display.outputs.insert(lock.hash(), lock.into());
+
+        let record = tx.history_record(1700000000);
+        assert_eq!(record.inputs.len(), 1);
+        assert_eq!(record.outputs.len(), tx.outputs().len());
+        let paid: Vec<_> = record
+            .outputs
+            .iter()
+            .filter(|o| o.recipient.is_some())
+            .collect();
+        assert_eq!(paid.len(), 1);
+        assert_eq!(paid[0].recipient, Some(recipient.to_string()));
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(&format!("\"id\":\"{}\"", tx.id)));
+        assert!(json.contains(&format!("\"fee\":\"{}\"", tx.total_fee())));
+        for note in tx.outputs() {
+            assert!(json.contains(&format!("\"amount\":\"{}\"", note.assets)));
+        }
+        assert_eq!(
+            serde_json::from_str::<TxHistoryRecord>(&json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn test_validate_structure() {
+        let (tx, _, _) = acceptance_fixture();
+        tx.validate_structure().unwrap();
+
+        let mut bad_id = tx.clone();
+        bad_id.id = Digest::from([1, 2, 3, 4, 5]);
+        assert_eq!(
+            bad_id.validate_structure(),
+            Err(AcceptanceError::IdMismatch)
+        );
+
+        let mut bad_proof = tx.clone();
+        let Spend::Witness(ws) = &mut bad_proof.spends.0[0].1 else {
+            panic!("expected witness spend");
+        };
+        ws.witness.lock_merkle_proof.proof.root = Digest::from([1, 2, 3, 4, 5]);
+        assert!(matches!(
+            bad_proof.validate_structure(),
+            Err(AcceptanceError::InvalidMerkleProof(_))
+        ));
+
+        let mut duplicate = tx.clone();
+        duplicate.spends.0.push(duplicate.spends.0[0].clone());
+        assert!(matches!(
+            duplicate.validate_structure(),
+            Err(AcceptanceError::DuplicateInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_pkh_signature_verify_all() {
+        let (tx, _, _) = acceptance_fixture();
+        let spend = &tx.spends.0[0].1;
+        let Spend::Witness(ws) = spend else {
+            panic!("expected witness spend");
+        };
+        let sig_hash = spend.sig_hash();
+        let signatures = &ws.witness.pkh_signature;
+        signatures.verify_all(&sig_hash).unwrap();
+
+        assert_eq!(
+            signatures.verify_all(&Digest::from([1, 2, 3, 4, 5])),
+            Err(SigError::InvalidSignature(signatures.0[0].0))
+        );
+
+        let mut tampered = signatures.clone();
+        tampered.0[0].0 = Digest::from([1, 2, 3, 4, 5]);
+        assert_eq!(
+            tampered.verify_all(&sig_hash),
+            Err(SigError::PkhMismatch(Digest::from([1, 2, 3, 4, 5])))
+        );
+    }
+
+    #[test]
+    fn test_check_acceptance_underpaid_fee() {
+        let (tx, notes, fee_per_word) = acceptance_fixture();
+        let fee = tx.spends.0[0].1.fee();
+        assert_eq!(
+            tx.check_acceptance(&notes, fee_per_word * 2),
+            Err(AcceptanceError::InsufficientFee(fee * 2, fee))
+        );
+
+        // Moving fee into the gift keeps the spend balanced, but underpays the fee.
+        let mut tx = tx;
+        let spend = &mut tx.spends.0[0].1;
+        *spend.fee_mut() -= 1;
+        spend.seeds_mut().0[0].gift += 1;
+        assert_eq!(
+            tx.check_acceptance(&notes, fee_per_word),
+            Err(AcceptanceError::InsufficientFee(fee, fee - 1))
+        );
+    }
+
+    #[test]
+    fn test_check_acceptance_broken_structure() {
+        let (tx, notes, fee_per_word) = acceptance_fixture();
+        let name = tx.spends.0[0].0.clone();
+
+        let mut dup = tx.clone();
+        dup.spends.0.push(dup.spends.0[0].clone());
+        assert_eq!(
+            dup.check_acceptance(&notes, fee_per_word),
+            Err(AcceptanceError::DuplicateInput(name.clone()))
+        );
+
+        let mut bad_proof = tx.clone();
+        let Spend::Witness(ws) = &mut bad_proof.spends.0[0].1 else {
+            panic!("expected witness spend");
+        };
+        ws.witness.lock_merkle_proof.proof.root = 1u64.hash();
+        assert_eq!(
+            bad_proof.check_acceptance(&notes, fee_per_word),
+            Err(AcceptanceError::InvalidMerkleProof(name.clone()))
+        );
+
+        let mut unbalanced = tx.clone();
+        unbalanced.spends.0[0].1.seeds_mut().0[0].gift += 1;
+        assert!(matches!(
+            unbalanced.check_acceptance(&notes, fee_per_word),
+            Err(AcceptanceError::Unbalanced { .. })
+        ));
+
+        let empty = RawTx::new(Spends::default());
+        assert_eq!(
+            empty.check_acceptance(&notes, fee_per_word),
+            Err(AcceptanceError::NoSpends)
+        );
+    }
 }
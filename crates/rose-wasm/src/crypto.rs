@@ -31,14 +31,14 @@ impl WasmSignature {
         self.s.clone()
     }
 
-    fn from_internal(sig: &Signature) -> Self {
+    pub(crate) fn from_internal(sig: &Signature) -> Self {
         Self {
             c: sig.c.to_be_bytes(),
             s: sig.s.to_be_bytes(),
         }
     }
 
-    fn to_internal(&self) -> Signature {
+    pub(crate) fn to_internal(&self) -> Signature {
         Signature {
             c: UBig::from_be_bytes(&self.c),
             s: UBig::from_be_bytes(&self.s),
@@ -55,6 +55,15 @@ pub struct WasmExtendedKey {
     pub public_key: Vec<u8>,
     #[wasm_bindgen(skip)]
     pub chain_code: Vec<u8>,
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub depth: u8,
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub parent_fingerprint: Vec<u8>,
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub child_number: u32,
 }
 
 #[wasm_bindgen(js_class = ExtendedKey)]
@@ -74,6 +83,21 @@ impl WasmExtendedKey {
         self.chain_code.clone()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    #[wasm_bindgen(getter, js_name = parentFingerprint)]
+    pub fn parent_fingerprint(&self) -> Vec<u8> {
+        self.parent_fingerprint.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = childNumber)]
+    pub fn child_number(&self) -> u32 {
+        self.child_number
+    }
+
     /// Derive a child key at the given index
     #[wasm_bindgen(js_name = deriveChild)]
     pub fn derive_child(&self, index: u32) -> Result<WasmExtendedKey, JsValue> {
@@ -106,10 +130,20 @@ impl WasmExtendedKey {
         let mut chain_code = [0u8; 32];
         chain_code.copy_from_slice(&self.chain_code);
 
+        // Missing for keys serialized before it was tracked, treated as zero.
+        let parent_fingerprint = match self.parent_fingerprint.len() {
+            0 => [0; 4],
+            4 => self.parent_fingerprint[..].try_into().unwrap(),
+            _ => return Err("Parent fingerprint must be 4 bytes".to_string()),
+        };
+
         Ok(ExtendedKey {
             private_key,
             public_key,
             chain_code,
+            depth: self.depth,
+            parent_fingerprint,
+            child_number: self.child_number,
         })
     }
 
@@ -118,6 +152,9 @@ impl WasmExtendedKey {
             private_key: key.private_key.as_ref().map(|pk| pk.to_be_bytes().to_vec()),
             public_key: key.public_key.to_be_bytes().to_vec(),
             chain_code: key.chain_code.to_vec(),
+            depth: key.depth,
+            parent_fingerprint: key.parent_fingerprint.to_vec(),
+            child_number: key.child_number,
         }
     }
 }
@@ -4,7 +4,7 @@ use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use ibig::UBig;
-use rose_crypto::PrivateKey;
+use rose_crypto::{PrivateKey, PublicKey};
 use rose_grpc_proto::pb::common::v1 as pb_v1;
 use rose_grpc_proto::pb::common::v2 as pb;
 use rose_nockchain_types::{
@@ -13,11 +13,14 @@ use rose_nockchain_types::{
     tx::{LockPrimitive, LockRoot, NockchainTx, RawTx, Seed, SpendCondition},
     Nicks,
 };
-use rose_nockchain_types::{Hax, LockTim, MissingUnlocks, Source, SpendBuilder};
+use rose_nockchain_types::{
+    DryRun, Hax, InputFee, LockTim, MissingUnlocks, SignableSpend, Source, SpendBuilder,
+};
 use rose_ztd::{cue, jam, Digest, Hashable as HashableTrait, NounDecode, NounEncode};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::crypto::WasmSignature;
 use crate::memo::memo_from_js;
 
 // ============================================================================
@@ -54,6 +57,28 @@ impl WasmDigest {
         }
     }
 
+    /// Hex encoding of the digest (40 bytes, big-endian), as an alternative to base58
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> Result<String, JsValue> {
+        let digest = self.to_internal().map_err(JsValue::from_str)?;
+        Ok(digest.to_hex())
+    }
+
+    /// Abbreviated form for logs (first 6 and last 4 characters), not meant to be parsed back
+    #[wasm_bindgen(js_name = shortValue)]
+    pub fn short_value(&self) -> Result<String, JsValue> {
+        let digest = self.to_internal().map_err(JsValue::from_str)?;
+        Ok(digest.short())
+    }
+
+    /// Parse a digest from its hex encoding, as returned by `toHex`. A `0x` prefix,
+    /// whitespace and uppercase digits are accepted.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(value: &str) -> Result<WasmDigest, JsValue> {
+        let digest = Digest::from_hex(value).map_err(JsValue::from_str)?;
+        Ok(WasmDigest::from_internal(&digest))
+    }
+
     #[wasm_bindgen(js_name = toProtobuf)]
     pub fn to_protobuf(&self) -> Result<JsValue, JsValue> {
         let digest = self.to_internal().map_err(JsValue::from_str)?;
@@ -381,7 +406,9 @@ pub struct WasmNote {
     pub name: WasmName,
     #[wasm_bindgen(skip)]
     pub note_data: WasmNoteData,
+    /// Serialized as a string, as amounts may exceed `Number.MAX_SAFE_INTEGER`.
     #[wasm_bindgen(skip)]
+    #[serde(with = "rose_grpc_proto::serde_u64_as_string")]
     pub assets: Nicks,
 }
 
@@ -796,6 +823,18 @@ impl WasmSpendCondition {
         Ok(WasmDigest::from_internal(&condition.first_name()))
     }
 
+    /// Describe the lock requirements, one clause per primitive.
+    ///
+    /// Returns a list of `{ type: "multisig", m, n }`, `{ type: "timelock", relMin, relMax,
+    /// absMin, absMax }`, `{ type: "hashlock", count }` or `{ type: "burn" }` objects.
+    #[wasm_bindgen]
+    pub fn describe(&self) -> Result<JsValue, JsValue> {
+        let condition = self
+            .to_internal()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&condition.describe()).map_err(|e| e.into())
+    }
+
     fn to_internal(&self) -> Result<SpendCondition, String> {
         let mut primitives = Vec::new();
         for prim in &self.primitives {
@@ -1076,6 +1115,72 @@ impl WasmTxBuilder {
         Ok(Self { builder })
     }
 
+    /// Serialize the builder (as CBOR), including its fee pool, e.g. to hand it to a co-signer.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&self.builder, &mut bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Deserialize a builder produced by `toBytes`
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmTxBuilder, JsValue> {
+        let builder =
+            ciborium::from_reader(bytes).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Self { builder })
+    }
+
+    /// Merge signatures from another serialized copy of this builder (see `toBytes`).
+    ///
+    /// Fails if the spends of the two builders differ.
+    #[wasm_bindgen(js_name = mergeSignatures)]
+    pub fn merge_signatures(&mut self, other: &[u8]) -> Result<(), JsValue> {
+        let other = Self::from_bytes(other)?;
+        self.builder
+            .merge_signatures(&other.builder)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Export what an external signer (e.g. a hardware wallet) needs to sign this transaction.
+    ///
+    /// Returns a list of `{ name, sigHash, spendCondition }`, one per spend. Sign each `sigHash`,
+    /// and attach the result with `attachSignature`.
+    #[wasm_bindgen(js_name = exportForSigning)]
+    pub fn export_for_signing(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.signable_spends()).map_err(|e| e.into())
+    }
+
+    fn signable_spends(&self) -> Vec<WasmSignableSpend> {
+        self.builder
+            .export_for_signing()
+            .spends
+            .iter()
+            .map(WasmSignableSpend::from_internal)
+            .collect()
+    }
+
+    /// Attach an externally produced signature to every spend it is valid for.
+    ///
+    /// Fails if the signature is not valid for any spend still missing the signer.
+    #[wasm_bindgen(js_name = attachSignature)]
+    pub fn attach_signature(
+        &mut self,
+        public_key_bytes: &[u8],
+        signature: &WasmSignature,
+    ) -> Result<(), JsValue> {
+        if public_key_bytes.len() != 97 {
+            return Err(JsValue::from_str("Public key must be 97 bytes"));
+        }
+        let public_key = PublicKey::from_be_bytes(public_key_bytes);
+        self.builder
+            .attach_signature(public_key, signature.to_internal())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
     /// Perform a simple-spend on this builder.
     ///
     /// It is HIGHLY recommended to not mix `simpleSpend` with other types of spends.
@@ -1238,6 +1343,32 @@ impl WasmTxBuilder {
         self.builder.calc_fee()
     }
 
+    /// Per-input components of the fee returned by `calcFee`.
+    ///
+    /// Returns a list of `{ nameFirst, nameLast, seedWords, witnessWords, unlockWords, fee }`, with
+    /// `fee` as a decimal string.
+    #[wasm_bindgen(js_name = feeBreakdown)]
+    pub fn fee_breakdown(&self) -> Result<JsValue, JsValue> {
+        let breakdown = self
+            .builder
+            .fee_breakdown()
+            .iter()
+            .map(WasmInputFee::from_internal)
+            .collect::<Vec<_>>();
+        serde_wasm_bindgen::to_value(&breakdown).map_err(|e| e.into())
+    }
+
+    /// Project the outcome of building this transaction, without validating it.
+    ///
+    /// Returns `{ outputs, fee, curFee, missingUnlocks, balanced }`, where `fee` is the same as
+    /// `calcFee`, and `curFee` is the same as `curFee`. Amounts (`fee`, `curFee` and output
+    /// `assets`) are decimal strings, as they may exceed `Number.MAX_SAFE_INTEGER`.
+    #[wasm_bindgen(js_name = dryRun)]
+    pub fn dry_run(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&WasmDryRun::from_internal(self.builder.dry_run()))
+            .map_err(|e| e.into())
+    }
+
     #[wasm_bindgen(js_name = allNotes)]
     pub fn all_notes(&self) -> WasmTxNotes {
         let mut ret = WasmTxNotes {
@@ -1459,6 +1590,81 @@ impl WasmMissingUnlocks {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmInputFee {
+    pub name_first: String,
+    pub name_last: String,
+    pub seed_words: u64,
+    pub witness_words: u64,
+    pub unlock_words: u64,
+    #[serde(with = "rose_grpc_proto::serde_u64_as_string")]
+    pub fee: Nicks,
+}
+
+impl WasmInputFee {
+    fn from_internal(internal: &InputFee) -> Self {
+        Self {
+            name_first: internal.name.first.to_string(),
+            name_last: internal.name.last.to_string(),
+            seed_words: internal.seed_words,
+            witness_words: internal.witness_words,
+            unlock_words: internal.unlock_words,
+            fee: internal.fee,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmSignableSpend {
+    pub name: WasmName,
+    pub sig_hash: WasmDigest,
+    pub spend_condition: WasmSpendCondition,
+}
+
+impl WasmSignableSpend {
+    fn from_internal(internal: &SignableSpend) -> Self {
+        Self {
+            name: WasmName::from_internal(&internal.name),
+            sig_hash: WasmDigest::from_internal(&internal.sig_hash),
+            spend_condition: WasmSpendCondition::from_internal(internal.spend_condition.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmDryRun {
+    pub outputs: Vec<WasmNote>,
+    #[serde(with = "rose_grpc_proto::serde_u64_as_string")]
+    pub fee: Nicks,
+    #[serde(with = "rose_grpc_proto::serde_u64_as_string")]
+    pub cur_fee: Nicks,
+    pub missing_unlocks: Vec<WasmMissingUnlocks>,
+    pub balanced: bool,
+}
+
+impl WasmDryRun {
+    fn from_internal(internal: DryRun) -> Self {
+        Self {
+            outputs: internal
+                .outputs
+                .into_iter()
+                .map(WasmNote::from_internal)
+                .collect(),
+            fee: internal.fee,
+            cur_fee: internal.cur_fee,
+            missing_unlocks: internal
+                .missing_unlocks
+                .iter()
+                .map(WasmMissingUnlocks::from_internal)
+                .collect(),
+            balanced: internal.balanced,
+        }
+    }
+}
+
 // ============================================================================
 // Wasm Raw Transaction
 // ============================================================================
@@ -1525,6 +1731,17 @@ impl WasmRawTx {
         Ok(Self::from_internal(&tx))
     }
 
+    /// Sanity-check the transaction without its input notes.
+    ///
+    /// Verifies that inputs are unique, merkle proofs match the spend conditions, and the
+    /// transaction id is consistent with its spends.
+    #[wasm_bindgen(js_name = validateStructure)]
+    pub fn validate_structure(&self) -> Result<(), JsValue> {
+        self.internal
+            .validate_structure()
+            .map_err(|v| JsValue::from_str(&v.to_string()))
+    }
+
     /// Calculate output notes from the transaction spends.
     #[wasm_bindgen]
     pub fn outputs(&self) -> Vec<WasmNote> {
@@ -1613,3 +1830,203 @@ impl WasmNockchainTx {
         WasmRawTx::from_internal(&self.internal.to_raw_tx())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rose_nockchain_types::LockClause;
+
+    /// A builder spending one 4294967296-nick note locked to an m-of-m pkh of `signers` keys,
+    /// and those keys: the test master key, then its children.
+    fn fixture_tx(signers: u64) -> (WasmTxBuilder, Vec<PrivateKey>) {
+        let mnemonic = bip39::Mnemonic::parse("dice domain inspire horse time initial monitor nature mass impose tone benefit vibrant dash kiss mosquito rice then color ribbon agent method drop fat").unwrap();
+        let master = rose_crypto::derive_master_key(&mnemonic.to_seed(""));
+        let keys: Vec<PrivateKey> = (0..signers)
+            .map(|i| match i {
+                0 => master.private_key.clone().unwrap(),
+                i => master.derive_child(i as u32).private_key.unwrap(),
+            })
+            .collect();
+        let spend_condition = SpendCondition::new_pkh(Pkh::new(
+            signers,
+            keys.iter().map(|k| k.public_key().hash()).collect(),
+        ));
+        let note = Note::new(
+            Version::V1,
+            13,
+            Name::new(spend_condition.first_name(), 1u64.hash()),
+            NoteData::empty(),
+            4294967296,
+        );
+
+        let mut tx = WasmTxBuilder::new(40000);
+        tx.builder
+            .simple_spend(
+                vec![(note, spend_condition)],
+                2u64.hash(),
+                1234567,
+                3u64.hash(),
+                false,
+                None,
+            )
+            .unwrap();
+        (tx, keys)
+    }
+
+    #[test]
+    fn test_digest_hex_roundtrip() {
+        let d =
+            WasmDigest::new("6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX".to_string());
+        let hex = d.to_hex().unwrap();
+        assert_eq!(hex.len(), 80);
+        assert_eq!(WasmDigest::from_hex(&hex).unwrap().value, d.value);
+    }
+
+    #[test]
+    fn test_digest_short_value() {
+        let d =
+            WasmDigest::new("6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX".to_string());
+        assert_eq!(d.short_value().unwrap(), "6psXuf…8XqX");
+    }
+
+    #[test]
+    fn test_tx_builder_merge_signatures() {
+        let (mut tx, keys) = fixture_tx(2);
+        let [key_a, key_b] = &keys[..] else {
+            unreachable!()
+        };
+        let key_bytes = |k: &PrivateKey| {
+            let mut bytes = [0u8; 32];
+            let be = k.0.to_be_bytes();
+            bytes[32 - be.len()..].copy_from_slice(&be);
+            bytes
+        };
+
+        let bytes = tx.to_bytes().unwrap();
+
+        // Each party signs their own copy.
+        let mut tx_a = WasmTxBuilder::from_bytes(&bytes).unwrap();
+        tx_a.sign(&key_bytes(key_a)).unwrap();
+        let mut tx_b = WasmTxBuilder::from_bytes(&bytes).unwrap();
+        tx_b.sign(&key_bytes(key_b)).unwrap();
+        assert!(tx_a.builder.clone().validate().is_err());
+
+        tx_a.merge_signatures(&tx_b.to_bytes().unwrap()).unwrap();
+        tx_a.validate().unwrap();
+
+        // A builder with different spends can't be merged.
+        let mut other = WasmTxBuilder::from_bytes(&bytes).unwrap();
+        other
+            .builder
+            .set_fee_and_balance_refund(tx.cur_fee() + 1, false, false)
+            .unwrap();
+        assert!(matches!(
+            tx.builder.merge_signatures(&other.builder),
+            Err(rose_nockchain_types::BuildError::SpendMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_note_assets_serialized_as_string() {
+        let assets = (1u64 << 53) + 1;
+        let note = WasmNote::from_internal(Note::new(
+            Version::V1,
+            13,
+            Name::new(1u64.hash(), 2u64.hash()),
+            NoteData::empty(),
+            assets,
+        ));
+
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&note, &mut bytes).unwrap();
+        let value: ciborium::Value = ciborium::from_reader(&bytes[..]).unwrap();
+        let assets_value = value
+            .as_map()
+            .unwrap()
+            .iter()
+            .find(|(k, _)| k.as_text() == Some("assets"))
+            .map(|(_, v)| v.clone())
+            .unwrap();
+        assert_eq!(assets_value.as_text(), Some("9007199254740993"));
+
+        let decoded: WasmNote = ciborium::from_reader(&bytes[..]).unwrap();
+        assert_eq!(decoded.assets, assets);
+    }
+
+    #[test]
+    fn test_tx_builder_attach_signature() {
+        let (mut tx, keys) = fixture_tx(1);
+        let private_key = &keys[0];
+        let public_key = private_key.public_key();
+
+        // Sign the exported sig hashes, as an external device would.
+        let spends = tx.signable_spends();
+        assert_eq!(spends.len(), 1);
+        let sig_hash = spends[0].sig_hash.to_internal().unwrap();
+        let signature = WasmSignature::from_internal(&private_key.sign(&sig_hash));
+
+        tx.attach_signature(&public_key.to_be_bytes(), &signature)
+            .unwrap();
+        tx.validate().unwrap();
+    }
+
+    #[test]
+    fn test_raw_tx_validate_structure() {
+        let (mut tx, keys) = fixture_tx(1);
+        tx.builder.sign(&keys[0]);
+        let raw_tx = tx.builder.build().to_raw_tx();
+
+        // JsValue errors can't be constructed natively, so the failure cases are covered by the
+        // `RawTx::validate_structure` tests.
+        let raw_tx = WasmRawTx::from_jam(&jam(raw_tx.to_noun())).unwrap();
+        raw_tx.validate_structure().unwrap();
+    }
+
+    #[test]
+    fn test_spend_condition_describe() {
+        let condition = WasmSpendCondition::new(vec![
+            WasmLockPrimitive::new_pkh(WasmPkh::single(
+                "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX".to_string(),
+            )),
+            WasmLockPrimitive::new_tim(WasmLockTim::coinbase()),
+        ]);
+        let clauses = condition.to_internal().unwrap().describe();
+        assert_eq!(
+            clauses,
+            vec![
+                LockClause::Multisig { m: 1, n: 1 },
+                LockClause::Timelock {
+                    rel_min: Some(100),
+                    rel_max: None,
+                    abs_min: None,
+                    abs_max: None,
+                },
+            ]
+        );
+        // The shape JS gets from `describe`
+        assert_eq!(
+            serde_json::to_value(&clauses).unwrap(),
+            serde_json::json!([
+                { "type": "multisig", "m": 1, "n": 1 },
+                {
+                    "type": "timelock",
+                    "relMin": 100,
+                    "relMax": null,
+                    "absMin": null,
+                    "absMax": null
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_dry_run_fee_matches_calc_fee() {
+        let (tx, _) = fixture_tx(1);
+
+        let dry_run = WasmDryRun::from_internal(tx.builder.dry_run());
+        assert_eq!(dry_run.fee, tx.calc_fee());
+        assert_eq!(dry_run.cur_fee, tx.cur_fee());
+        assert!(dry_run.balanced);
+        assert_eq!(dry_run.outputs.len(), 2);
+    }
+}
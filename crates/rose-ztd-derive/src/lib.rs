@@ -1,12 +1,18 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields};
+use syn::{parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Fields, Generics};
 
 /// Derive macro for implementing the Hashable trait.
 ///
 /// This macro automatically implements Hashable for structs by creating
 /// nested tuples of field references and calling .hash() on them.
 ///
+/// Enum variants are numbered in declaration order, ignoring explicit discriminants and
+/// `#[repr]`. A unit variant hashes as its index, other variants as `(index, fields)`.
+///
+/// Struct fields marked `#[noun(skip)]` are left out, like for [`derive_noun_encode`], so the
+/// hash is the same after a noun roundtrip.
+///
 /// # Example
 ///
 /// ```ignore
@@ -27,15 +33,23 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields};
 ///     }
 /// }
 /// ```
-#[proc_macro_derive(Hashable)]
+#[proc_macro_derive(Hashable, attributes(noun))]
 pub fn derive_hashable(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    if let Err(e) = check_noun_attrs(&input.data) {
+        return e.to_compile_error().into();
+    }
 
     let hash_expr = match &input.data {
         Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => {
-                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let field_names: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_skipped(f))
+                    .map(|f| &f.ident)
+                    .collect();
 
                 if field_names.is_empty() {
                     // Empty struct hashes as unit
@@ -50,15 +64,21 @@ pub fn derive_hashable(input: TokenStream) -> TokenStream {
                 }
             }
             Fields::Unnamed(fields) => {
-                let field_count = fields.unnamed.len();
+                let indices: Vec<_> = fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| !is_skipped(f))
+                    .map(|(i, _)| syn::Index::from(i))
+                    .collect();
 
-                if field_count == 0 {
+                if indices.is_empty() {
                     quote! { ().hash() }
-                } else if field_count == 1 {
-                    quote! { self.0.hash() }
+                } else if indices.len() == 1 {
+                    let index = &indices[0];
+                    quote! { self.#index.hash() }
                 } else {
                     // Build nested tuples for tuple structs using indices
-                    let indices: Vec<_> = (0..field_count).map(syn::Index::from).collect();
                     build_nested_tuple_indexed(&indices)
                 }
             }
@@ -66,14 +86,7 @@ pub fn derive_hashable(input: TokenStream) -> TokenStream {
                 quote! { ().hash() }
             }
         },
-        Data::Enum(_) => {
-            return syn::Error::new_spanned(
-                &input,
-                "Hashable derive macro does not support enums yet",
-            )
-            .to_compile_error()
-            .into();
-        }
+        Data::Enum(data) => build_enum_hash(data),
         Data::Union(_) => {
             return syn::Error::new_spanned(
                 &input,
@@ -84,8 +97,11 @@ pub fn derive_hashable(input: TokenStream) -> TokenStream {
         }
     };
 
+    let generics = add_trait_bounds(input.generics.clone(), parse_quote!(rose_ztd::Hashable));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     TokenStream::from(quote! {
-        impl rose_ztd::Hashable for #name {
+        impl #impl_generics rose_ztd::Hashable for #name #ty_generics #where_clause {
             fn hash(&self) -> rose_ztd::Digest {
                 #hash_expr
             }
@@ -93,6 +109,51 @@ pub fn derive_hashable(input: TokenStream) -> TokenStream {
     })
 }
 
+/// Build the `hash` body for an enum, see [`derive_hashable`].
+fn build_enum_hash(data: &DataEnum) -> proc_macro2::TokenStream {
+    let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+        let index = index as u64;
+        let ident = &variant.ident;
+        match &variant.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => {
+                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                quote! {
+                    Self::#ident { #( #field_names ),* } => {
+                        rose_ztd::Hashable::hash(&(#index, (#( #field_names ),* )))
+                    }
+                }
+            }
+            Fields::Unnamed(fields) if !fields.unnamed.is_empty() => {
+                let bindings: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| quote::format_ident!("f{}", i))
+                    .collect();
+                quote! {
+                    Self::#ident(#( #bindings ),*) => {
+                        rose_ztd::Hashable::hash(&(#index, (#( #bindings ),* )))
+                    }
+                }
+            }
+            Fields::Named(_) => quote! { Self::#ident {} => rose_ztd::Hashable::hash(&#index) },
+            Fields::Unnamed(_) => quote! { Self::#ident() => rose_ztd::Hashable::hash(&#index) },
+            Fields::Unit => quote! { Self::#ident => rose_ztd::Hashable::hash(&#index) },
+        }
+    });
+
+    quote! {
+        match self {
+            #( #arms, )*
+        }
+    }
+}
+
+/// Bound every type parameter by `bound`, e.g. `T: rose_ztd::NounEncode`.
+fn add_trait_bounds(mut generics: Generics, bound: syn::TypeParamBound) -> Generics {
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+    generics
+}
+
 /// Build nested tuple expression for named fields: (&self.x, &(&self.y, &self.z))
 fn build_nested_tuple(field_names: &[&Option<syn::Ident>]) -> proc_macro2::TokenStream {
     let mut iter = field_names.iter().rev();
@@ -122,15 +183,30 @@ fn build_nested_tuple_indexed(indices: &[syn::Index]) -> proc_macro2::TokenStrea
 }
 
 /// Derive macro for implementing the `NounEncode` trait.
-#[proc_macro_derive(NounEncode)]
+///
+/// Enum variants are numbered in declaration order, ignoring explicit discriminants, so
+/// reordering variants changes the encoding. A unit variant encodes as its bare index atom,
+/// other variants as `[index payload]`, where the payload follows the struct convention: the
+/// field itself if there is only one, and a nested tuple of the fields otherwise.
+///
+/// Struct fields marked `#[noun(skip)]` are left out, see [`derive_noun_decode`].
+#[proc_macro_derive(NounEncode, attributes(noun))]
 pub fn derive_noun_encode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    if let Err(e) = check_noun_attrs(&input.data) {
+        return e.to_compile_error().into();
+    }
 
     let impl_body = match &input.data {
         Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => {
-                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let field_names: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_skipped(f))
+                    .map(|f| &f.ident)
+                    .collect();
 
                 if field_names.is_empty() {
                     quote! { rose_ztd::NounEncode::to_noun(&0u64) }
@@ -143,28 +219,27 @@ pub fn derive_noun_encode(input: TokenStream) -> TokenStream {
                 }
             }
             Fields::Unnamed(fields) => {
-                let field_count = fields.unnamed.len();
+                let indices: Vec<_> = fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| !is_skipped(f))
+                    .map(|(i, _)| syn::Index::from(i))
+                    .collect();
 
-                if field_count == 0 {
+                if indices.is_empty() {
                     quote! { rose_ztd::NounEncode::to_noun(&0u64) }
-                } else if field_count == 1 {
-                    quote! { rose_ztd::NounEncode::to_noun(&self.0) }
+                } else if indices.len() == 1 {
+                    let index = &indices[0];
+                    quote! { rose_ztd::NounEncode::to_noun(&self.#index) }
                 } else {
-                    let indices: Vec<_> = (0..field_count).map(syn::Index::from).collect();
                     let tuple_expr = build_nested_tuple_refs_indexed(&indices);
                     quote! { rose_ztd::NounEncode::to_noun(&#tuple_expr) }
                 }
             }
             Fields::Unit => quote! { rose_ztd::NounEncode::to_noun(&0u64) },
         },
-        Data::Enum(_) => {
-            return syn::Error::new_spanned(
-                &input,
-                "NounEncode derive macro does not support enums yet",
-            )
-            .to_compile_error()
-            .into();
-        }
+        Data::Enum(data) => build_enum_encode(data),
         Data::Union(_) => {
             return syn::Error::new_spanned(
                 &input,
@@ -175,8 +250,11 @@ pub fn derive_noun_encode(input: TokenStream) -> TokenStream {
         }
     };
 
+    let generics = add_trait_bounds(input.generics.clone(), parse_quote!(rose_ztd::NounEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     TokenStream::from(quote! {
-        impl rose_ztd::NounEncode for #name {
+        impl #impl_generics rose_ztd::NounEncode for #name #ty_generics #where_clause {
             fn to_noun(&self) -> rose_ztd::Noun {
                 #impl_body
             }
@@ -185,20 +263,46 @@ pub fn derive_noun_encode(input: TokenStream) -> TokenStream {
 }
 
 /// Derive macro for implementing the `NounDecode` trait.
-#[proc_macro_derive(NounDecode)]
+///
+/// Enum variants are numbered in declaration order, ignoring explicit discriminants. A unit
+/// variant decodes from its bare index atom, other variants from `[index payload]`, where the
+/// payload follows the struct convention: the field itself if there is only one, and a nested
+/// tuple of the fields otherwise.
+///
+/// Struct fields marked `#[noun(skip)]`, such as caches, are not part of the noun and are set to
+/// `Default::default()`.
+#[proc_macro_derive(NounDecode, attributes(noun))]
 pub fn derive_noun_decode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    if let Err(e) = check_noun_attrs(&input.data) {
+        return e.to_compile_error().into();
+    }
 
     let impl_body = match &input.data {
         Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => {
-                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let field_names: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_skipped(f))
+                    .map(|f| &f.ident)
+                    .collect();
+                let skipped: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_skipped(f))
+                    .map(|f| {
+                        let ident = &f.ident;
+                        let default = skipped_default(f);
+                        quote! { #ident: #default }
+                    })
+                    .collect();
 
                 if field_names.is_empty() {
                     quote! {
                         if noun == rose_ztd::noun::atom(0) {
-                            Some(Self)
+                            Some(Self { #( #skipped ),* })
                         } else {
                             None
                         }
@@ -207,31 +311,52 @@ pub fn derive_noun_decode(input: TokenStream) -> TokenStream {
                     quote! {
                         let (#( #field_names ),* ) = rose_ztd::NounDecode::from_noun(noun)?;
                         Some(Self {
-                            #( #field_names ),*
+                            #( #field_names, )*
+                            #( #skipped ),*
                         })
                     }
                 }
             }
             Fields::Unnamed(fields) => {
-                let field_count = fields.unnamed.len();
+                let bindings: Vec<_> = fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| !is_skipped(f))
+                    .map(|(i, _)| quote::format_ident!("f{}", i))
+                    .collect();
+                let values: Vec<_> = fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        if is_skipped(f) {
+                            skipped_default(f)
+                        } else {
+                            let binding = quote::format_ident!("f{}", i);
+                            quote! { #binding }
+                        }
+                    })
+                    .collect();
 
-                if field_count == 0 {
+                if bindings.is_empty() {
                     quote! {
                         if noun == rose_ztd::noun::atom(0) {
-                            Some(Self)
+                            Some(Self(#( #values ),*))
                         } else {
                             None
                         }
                     }
-                } else if field_count == 1 {
-                    quote! { Some(Self(rose_ztd::NounDecode::from_noun(noun)?)) }
+                } else if bindings.len() == 1 {
+                    let binding = &bindings[0];
+                    quote! {
+                        let #binding = rose_ztd::NounDecode::from_noun(noun)?;
+                        Some(Self(#( #values ),*))
+                    }
                 } else {
-                    let indices: Vec<_> = (0..field_count).map(syn::Index::from).collect();
                     quote! {
-                        let tup = rose_ztd::NounDecode::from_noun(noun)?;
-                        Some(Self(
-                            #( tup.#indices ),*
-                        ))
+                        let (#( #bindings ),* ) = rose_ztd::NounDecode::from_noun(noun)?;
+                        Some(Self(#( #values ),*))
                     }
                 }
             }
@@ -243,14 +368,7 @@ pub fn derive_noun_decode(input: TokenStream) -> TokenStream {
                 }
             },
         },
-        Data::Enum(_) => {
-            return syn::Error::new_spanned(
-                &input,
-                "NounDecode derive macro does not support enums yet",
-            )
-            .to_compile_error()
-            .into();
-        }
+        Data::Enum(data) => build_enum_decode(data),
         Data::Union(_) => {
             return syn::Error::new_spanned(
                 &input,
@@ -261,8 +379,11 @@ pub fn derive_noun_decode(input: TokenStream) -> TokenStream {
         }
     };
 
+    let generics = add_trait_bounds(input.generics.clone(), parse_quote!(rose_ztd::NounDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     TokenStream::from(quote! {
-        impl rose_ztd::NounDecode for #name {
+        impl #impl_generics rose_ztd::NounDecode for #name #ty_generics #where_clause {
             fn from_noun(noun: &rose_ztd::Noun) -> Option<Self> {
                 #impl_body
             }
@@ -297,3 +418,143 @@ fn build_nested_tuple_refs_indexed(indices: &[syn::Index]) -> proc_macro2::Token
 
     result
 }
+
+/// Check `#[noun(...)]` attributes: only `skip` is supported, and only on struct fields.
+fn check_noun_attrs(data: &Data) -> syn::Result<()> {
+    let check = |field: &syn::Field, allow_skip: bool| -> syn::Result<()> {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("noun")) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("skip") {
+                    return Err(meta.error("unsupported noun attribute, expected `skip`"));
+                }
+                if !allow_skip {
+                    return Err(meta.error("`#[noun(skip)]` is not supported on enum variants"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    };
+    match data {
+        Data::Struct(data) => data.fields.iter().try_for_each(|f| check(f, true)),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|v| v.fields.iter())
+            .try_for_each(|f| check(f, false)),
+        Data::Union(_) => Ok(()),
+    }
+}
+
+/// Whether the field is marked `#[noun(skip)]`, once checked by [`check_noun_attrs`].
+fn is_skipped(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|a| a.path().is_ident("noun"))
+}
+
+/// `Default::default()` for a skipped field, spanned so that a missing `Default` impl is
+/// reported on the field.
+fn skipped_default(field: &syn::Field) -> proc_macro2::TokenStream {
+    let ty = &field.ty;
+    quote::quote_spanned! {syn::spanned::Spanned::span(ty)=>
+        <#ty as ::core::default::Default>::default()
+    }
+}
+
+/// Build the `to_noun` body for an enum, see [`derive_noun_encode`].
+fn build_enum_encode(data: &DataEnum) -> proc_macro2::TokenStream {
+    let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+        let index = index as u64;
+        let ident = &variant.ident;
+        match &variant.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => {
+                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                quote! {
+                    Self::#ident { #( #field_names ),* } => {
+                        rose_ztd::NounEncode::to_noun(&(#index, (#( #field_names ),* )))
+                    }
+                }
+            }
+            Fields::Unnamed(fields) if !fields.unnamed.is_empty() => {
+                let bindings: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| quote::format_ident!("f{}", i))
+                    .collect();
+                quote! {
+                    Self::#ident(#( #bindings ),*) => {
+                        rose_ztd::NounEncode::to_noun(&(#index, (#( #bindings ),* )))
+                    }
+                }
+            }
+            Fields::Named(_) => {
+                quote! { Self::#ident {} => rose_ztd::NounEncode::to_noun(&#index) }
+            }
+            Fields::Unnamed(_) => {
+                quote! { Self::#ident() => rose_ztd::NounEncode::to_noun(&#index) }
+            }
+            Fields::Unit => quote! { Self::#ident => rose_ztd::NounEncode::to_noun(&#index) },
+        }
+    });
+
+    quote! {
+        match self {
+            #( #arms, )*
+        }
+    }
+}
+
+/// Build the `from_noun` body for an enum, see [`derive_noun_decode`].
+fn build_enum_decode(data: &DataEnum) -> proc_macro2::TokenStream {
+    let mut unit_arms = Vec::new();
+    let mut cell_arms = Vec::new();
+
+    for (index, variant) in data.variants.iter().enumerate() {
+        let index = index as u64;
+        let ident = &variant.ident;
+        match &variant.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => {
+                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                cell_arms.push(quote! {
+                    #index => {
+                        let (#( #field_names ),* ) = rose_ztd::NounDecode::from_noun(tail)?;
+                        Some(Self::#ident { #( #field_names ),* })
+                    }
+                });
+            }
+            Fields::Unnamed(fields) if !fields.unnamed.is_empty() => {
+                let bindings: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| quote::format_ident!("f{}", i))
+                    .collect();
+                cell_arms.push(quote! {
+                    #index => {
+                        let (#( #bindings ),* ) = rose_ztd::NounDecode::from_noun(tail)?;
+                        Some(Self::#ident(#( #bindings ),*))
+                    }
+                });
+            }
+            Fields::Named(_) => unit_arms.push(quote! { #index => Some(Self::#ident {}) }),
+            Fields::Unnamed(_) => unit_arms.push(quote! { #index => Some(Self::#ident()) }),
+            Fields::Unit => unit_arms.push(quote! { #index => Some(Self::#ident) }),
+        }
+    }
+
+    quote! {
+        match noun {
+            rose_ztd::Noun::Atom(_) => {
+                let index: u64 = rose_ztd::NounDecode::from_noun(noun)?;
+                #[allow(clippy::match_single_binding)]
+                match index {
+                    #( #unit_arms, )*
+                    _ => None,
+                }
+            }
+            rose_ztd::Noun::Cell(head, tail) => {
+                let index: u64 = rose_ztd::NounDecode::from_noun(head)?;
+                let tail: &rose_ztd::Noun = tail;
+                #[allow(clippy::match_single_binding)]
+                match index {
+                    #( #cell_arms, )*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use rose_ztd::noun::{atom, cons, tuple};
+use rose_ztd::{Hashable, Noun, NounDecode, NounEncode};
+use rose_ztd_derive::{Hashable, NounDecode, NounEncode};
+
+#[derive(Debug, Clone, PartialEq, Hashable, NounEncode, NounDecode)]
+enum Message {
+    Ping,
+    Single(u64),
+    Pair(u64, bool),
+    Named {
+        height: u64,
+        hash: u64,
+        final_: bool,
+    },
+    Empty {},
+}
+
+#[test]
+fn test_decode_enum() {
+    assert_eq!(Message::from_noun(&atom(0)), Some(Message::Ping));
+    assert_eq!(Message::from_noun(&atom(4)), Some(Message::Empty {}));
+    assert_eq!(
+        Message::from_noun(&cons(atom(1), atom(7))),
+        Some(Message::Single(7))
+    );
+    assert_eq!(
+        Message::from_noun(&cons(atom(2), cons(atom(7), atom(1)))),
+        Some(Message::Pair(7, false))
+    );
+    assert_eq!(
+        Message::from_noun(&cons(atom(3), tuple(&[atom(10), atom(11), atom(0)]))),
+        Some(Message::Named {
+            height: 10,
+            hash: 11,
+            final_: true
+        })
+    );
+}
+
+#[test]
+fn test_decode_enum_rejects_mismatched_shapes() {
+    // Unknown index
+    assert_eq!(Message::from_noun(&atom(5)), None);
+    assert_eq!(Message::from_noun(&cons(atom(5), atom(0))), None);
+    // Unit variants only decode from a bare atom, others only from a cell
+    assert_eq!(Message::from_noun(&cons(atom(0), atom(0))), None);
+    assert_eq!(Message::from_noun(&atom(1)), None);
+    // Wrong payload
+    assert_eq!(Message::from_noun(&cons(atom(2), atom(7))), None);
+    let cell_head: Noun = cons(cons(atom(1), atom(1)), atom(7));
+    assert_eq!(Message::from_noun(&cell_head), None);
+}
+
+#[test]
+fn test_encode_enum_matches_reference() {
+    fn reference(message: &Message) -> Noun {
+        match message {
+            Message::Ping => atom(0),
+            Message::Single(a) => cons(atom(1), atom(*a)),
+            Message::Pair(a, b) => cons(atom(2), cons(atom(*a), b.to_noun())),
+            Message::Named {
+                height,
+                hash,
+                final_,
+            } => cons(
+                atom(3),
+                tuple(&[atom(*height), atom(*hash), final_.to_noun()]),
+            ),
+            Message::Empty {} => atom(4),
+        }
+    }
+
+    let messages = [
+        Message::Ping,
+        Message::Single(7),
+        Message::Pair(7, false),
+        Message::Named {
+            height: 10,
+            hash: 11,
+            final_: true,
+        },
+        Message::Empty {},
+    ];
+    for message in messages {
+        let noun = message.to_noun();
+        assert_eq!(noun, reference(&message), "{message:?}");
+        assert_eq!(Message::from_noun(&noun), Some(message));
+    }
+}
+
+#[test]
+fn test_hash_enum() {
+    let named = |height| Message::Named {
+        height,
+        hash: 11,
+        final_: true,
+    };
+    assert_eq!(named(10).hash(), named(10).hash());
+    assert_ne!(named(10).hash(), named(12).hash());
+    assert_eq!(named(10).hash(), (3u64, (10u64, 11u64, true)).hash());
+
+    assert_eq!(Message::Ping.hash(), 0u64.hash());
+    assert_eq!(Message::Single(7).hash(), (1u64, 7u64).hash());
+    assert_eq!(Message::Pair(7, false).hash(), (2u64, 7u64, false).hash());
+
+    // Same payload, different variants
+    assert_ne!(Message::Ping.hash(), Message::Empty {}.hash());
+    assert_ne!(Message::Single(7).hash(), Message::Pair(7, false).hash());
+}
+
+#[derive(Debug, Clone, PartialEq, Hashable, NounEncode, NounDecode)]
+struct Cached {
+    height: u64,
+    #[noun(skip)]
+    cache: Option<u64>,
+    final_: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Hashable, NounEncode, NounDecode)]
+struct CachedTuple(#[noun(skip)] Vec<u64>, u64);
+
+#[test]
+fn test_noun_skip() {
+    let cached = Cached {
+        height: 10,
+        cache: Some(3),
+        final_: true,
+    };
+    let noun = cached.to_noun();
+    assert_eq!(noun, (10u64, true).to_noun());
+    assert_eq!(
+        Cached::from_noun(&noun),
+        Some(Cached {
+            cache: None,
+            ..cached
+        })
+    );
+
+    // Skipped fields don't change the hash either, so it survives a noun roundtrip
+    assert_eq!(cached.hash(), (10u64, true).hash());
+    assert_eq!(Cached::from_noun(&noun).unwrap().hash(), cached.hash());
+
+    let tuple = CachedTuple(vec![1, 2], 7);
+    assert_eq!(tuple.to_noun(), atom(7));
+    assert_eq!(
+        CachedTuple::from_noun(&tuple.to_noun()),
+        Some(CachedTuple(vec![], 7))
+    );
+    assert_eq!(tuple.hash(), 7u64.hash());
+}
+
+#[derive(Debug, Clone, PartialEq, Hashable, NounEncode, NounDecode)]
+struct Wrapper<T>(T);
+
+#[derive(Debug, Clone, PartialEq, Hashable, NounEncode, NounDecode)]
+struct Pair<A, B: Clone>
+where
+    A: Clone,
+{
+    first: A,
+    second: B,
+}
+
+#[test]
+fn test_generics() {
+    let wrapper = Wrapper(7u64);
+    assert_eq!(wrapper.to_noun(), atom(7));
+    assert_eq!(wrapper.hash(), 7u64.hash());
+    assert_eq!(Wrapper::<u64>::from_noun(&atom(7)), Some(wrapper));
+
+    let pair = Pair {
+        first: Wrapper(1u64),
+        second: true,
+    };
+    assert_eq!(pair.to_noun(), (1u64, true).to_noun());
+    assert_eq!(pair.hash(), (1u64, true).hash());
+    assert_eq!(Pair::from_noun(&pair.to_noun()), Some(pair));
+}
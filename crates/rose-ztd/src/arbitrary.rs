@@ -0,0 +1,56 @@
+//! [`Arbitrary`] impls for fuzzing, behind the `arbitrary` feature.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Belt, Digest, PRIME};
+
+/// Always in-field.
+impl<'a> Arbitrary<'a> for Belt {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Belt(u.int_in_range(0..=PRIME - 1)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u64::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Digest {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Digest(u.arbitrary()?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[Belt; 5]>::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_arbitrary_belt_in_field() {
+        // xorshift, so the bytes cover the whole u64 range including values >= PRIME.
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let bytes: Vec<u8> = (0..1 << 16)
+            .flat_map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()
+            })
+            .collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..10_000 {
+            let belt = Belt::arbitrary(&mut u).unwrap();
+            assert!(belt.0 < PRIME, "{}", belt.0);
+            let digest = Digest::arbitrary(&mut u).unwrap();
+            assert!(digest.0.iter().all(|b| b.0 < PRIME));
+        }
+
+        let ones = [0xff; 8];
+        assert!(Belt::arbitrary(&mut Unstructured::new(&ones)).unwrap().0 < PRIME);
+    }
+}
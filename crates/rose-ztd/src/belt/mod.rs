@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
 use core::ops::{Add, Div, Mul, Neg, Sub};
+use ibig::UBig;
 use num_traits::Pow;
 use serde::{Deserialize, Serialize};
 
@@ -15,10 +16,28 @@ pub const PRIME_128: u128 = 18446744069414584321;
 const RP: u128 = 340282366841710300967557013911933812736;
 pub const R2: u128 = 18446744065119617025;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Default, Serialize)]
 #[repr(transparent)]
 pub struct Belt(pub u64);
 
+impl<'de> Deserialize<'de> for Belt {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Same layout as the derived impl, plus a field check: arithmetic on out-of-field values
+        // is only caught by debug assertions.
+        #[derive(Deserialize)]
+        #[serde(rename = "Belt")]
+        struct Raw(u64);
+
+        let Raw(value) = Raw::deserialize(deserializer)?;
+        if !based_check(value) {
+            return Err(serde::de::Error::custom(format_args!(
+                "belt {value} is out of field"
+            )));
+        }
+        Ok(Belt(value))
+    }
+}
+
 impl Belt {
     pub fn from_bytes(bytes: &[u8]) -> Vec<Belt> {
         let mut belts = Vec::new();
@@ -39,6 +58,21 @@ impl Belt {
     }
 }
 
+/// Pack belts holding 32-bit limbs, least significant limb first, into an integer.
+///
+/// This is the layout used for signature scalars. Panics if a belt does not fit in 32 bits.
+pub fn belts_to_ubig(belts: &[Belt]) -> UBig {
+    UBig::from_le_bytes(&Belt::to_bytes(belts))
+}
+
+/// Inverse of [`belts_to_ubig`]: split `n` into exactly `count` 32-bit limbs, least significant
+/// limb first. Limbs beyond `count` are dropped.
+pub fn ubig_to_belts(n: &UBig, count: usize) -> Vec<Belt> {
+    let mut belts = Belt::from_bytes(&n.to_le_bytes());
+    belts.resize(count, Belt(0));
+    belts
+}
+
 pub fn based_check(a: u64) -> bool {
     a < PRIME
 }
@@ -128,6 +162,104 @@ impl Belt {
     pub fn inv(&self) -> Self {
         Belt(binv(self.0))
     }
+
+    /// Invert every element of `elems` in place with a single field inversion (Montgomery's
+    /// trick). Zeros are left as zero.
+    pub fn batch_inv(elems: &mut [Belt]) {
+        // prefix[i] is the product of the nonzero elements before index i
+        let mut prefix = Vec::with_capacity(elems.len());
+        let mut acc = Belt::one();
+        for e in elems.iter() {
+            prefix.push(acc);
+            if !e.is_zero() {
+                acc = acc * *e;
+            }
+        }
+
+        // acc_inv is the inverse of the product of the nonzero elements up to and including i
+        let mut acc_inv = acc.inv();
+        for (e, p) in elems.iter_mut().zip(prefix).rev() {
+            if e.is_zero() {
+                continue;
+            }
+            let inv = acc_inv * p;
+            acc_inv = acc_inv * *e;
+            *e = inv;
+        }
+    }
+
+    /// Square root, if `self` is a quadratic residue. Of the two roots `r` and `-r`, returns the
+    /// one with the smaller canonical representative.
+    ///
+    /// Tonelli-Shanks, using `PRIME - 1 = 2^32 * (2^32 - 1)`.
+    pub fn sqrt(&self) -> Option<Self> {
+        // 2^32 - 1
+        const ODD: u64 = 0xffff_ffff;
+        // 7 generates the multiplicative group, so it is not a square
+        const NON_RESIDUE: Belt = Belt(7);
+
+        if self.is_zero() {
+            return Some(Belt::zero());
+        }
+        // Euler's criterion
+        if !self.pow((PRIME - 1) / 2).is_one() {
+            return None;
+        }
+
+        let mut m = 32;
+        let mut c = NON_RESIDUE.pow(ODD);
+        let mut t = self.pow(ODD);
+        let mut r = self.pow(ODD.div_ceil(2));
+        while !t.is_one() {
+            // least i with t^(2^i) == 1, which is < m since t has order dividing 2^(m-1)
+            let mut i = 0;
+            let mut t2 = t;
+            while !t2.is_one() {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b = b * b;
+            }
+            m = i;
+            c = b * b;
+            t = t * c;
+            r = r * b;
+        }
+
+        let neg = -r;
+        Some(if neg.0 < r.0 { neg } else { r })
+    }
+
+    /// Parse a hex field element, as printed by `{:x}` (an optional `0x` prefix is allowed).
+    pub fn from_hex(s: &str) -> Result<Self, FieldError> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        let value = u64::from_str_radix(s, 16).map_err(|_| FieldError::InvalidHex)?;
+        if !based_check(value) {
+            return Err(FieldError::OutOfField(value));
+        }
+        Ok(Belt(value))
+    }
+
+    /// Field addition, also returning whether the integer sum reached `PRIME`, i.e. a modular
+    /// reduction occurred. Useful to catch integer (e.g. `Nicks`) arithmetic done in the field.
+    #[inline(always)]
+    pub fn add_with_reduction_flag(self, rhs: Self) -> (Self, bool) {
+        let reduced = (self.0 as u128) + (rhs.0 as u128) >= PRIME_128;
+        (self + rhs, reduced)
+    }
+
+    /// Field subtraction, also returning whether the integer difference was negative, i.e. a
+    /// modular reduction occurred.
+    #[inline(always)]
+    pub fn sub_with_reduction_flag(self, rhs: Self) -> (Self, bool) {
+        (self - rhs, self.0 < rhs.0)
+    }
 }
 
 impl Add for Belt {
@@ -182,6 +314,24 @@ impl Pow<usize> for Belt {
     }
 }
 
+impl Pow<u64> for Belt {
+    type Output = Self;
+
+    #[inline(always)]
+    fn pow(self, rhs: u64) -> Self::Output {
+        Belt(bpow(self.0, rhs))
+    }
+}
+
+impl Pow<Belt> for Belt {
+    type Output = Self;
+
+    #[inline(always)]
+    fn pow(self, rhs: Belt) -> Self::Output {
+        Belt(bpow(self.0, rhs.0))
+    }
+}
+
 impl Div for Belt {
     type Output = Self;
 
@@ -192,6 +342,30 @@ impl Div for Belt {
     }
 }
 
+impl core::iter::Sum for Belt {
+    fn sum<I: Iterator<Item = Belt>>(iter: I) -> Self {
+        iter.fold(Belt::zero(), |acc, b| acc + b)
+    }
+}
+
+impl<'a> core::iter::Sum<&'a Belt> for Belt {
+    fn sum<I: Iterator<Item = &'a Belt>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl core::iter::Product for Belt {
+    fn product<I: Iterator<Item = Belt>>(iter: I) -> Self {
+        iter.fold(Belt::one(), |acc, b| acc * b)
+    }
+}
+
+impl<'a> core::iter::Product<&'a Belt> for Belt {
+    fn product<I: Iterator<Item = &'a Belt>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
 impl PartialEq<u64> for Belt {
     #[inline(always)]
     fn eq(&self, other: &u64) -> bool {
@@ -251,9 +425,37 @@ impl From<Belt> for u32 {
     }
 }
 
+impl core::fmt::Debug for Belt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Out-of-field values only come from bugs (or raw construction), so make them stand out.
+        if self.0 < PRIME {
+            write!(f, "Belt({})", self.0)
+        } else {
+            write!(f, "Belt({} /* OUT OF FIELD */)", self.0)
+        }
+    }
+}
+
+impl core::fmt::LowerHex for Belt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl core::hash::Hash for Belt {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        // Hash the reduced value, so an out-of-field belt hashes the same as its canonical form.
+        (self.0 % PRIME).hash(state)
+    }
+}
+
 #[derive(Debug)]
 pub enum FieldError {
     OrderedRootError,
+    /// Not a hex number that fits in 64 bits
+    InvalidHex,
+    /// Parsed value is not below `PRIME`
+    OutOfField(u64),
 }
 
 #[inline(always)]
@@ -402,3 +604,273 @@ pub fn bpow(mut a: u64, mut b: u64) -> u64 {
     }
     reduce((c as u128) * (a as u128))
 }
+
+const POW_WINDOW_BITS: u32 = 4;
+const POW_WINDOW_SIZE: usize = 1 << POW_WINDOW_BITS;
+const POW_WINDOWS: usize = (u64::BITS / POW_WINDOW_BITS) as usize;
+
+/// Fixed-base exponentiation table, for raising the same base to many exponents.
+///
+/// Precomputes `base^(d * 16^i)` for every 4-bit digit `d` and window `i`, so each
+/// [`BeltPowTable::pow`] costs at most 16 multiplications and no squarings.
+#[derive(Clone)]
+pub struct BeltPowTable {
+    table: Vec<[u64; POW_WINDOW_SIZE]>,
+}
+
+impl BeltPowTable {
+    pub fn new(base: Belt) -> Self {
+        based!(base.0);
+
+        let mut table = Vec::with_capacity(POW_WINDOWS);
+        let mut b = base.0;
+        for _ in 0..POW_WINDOWS {
+            let mut row = [1u64; POW_WINDOW_SIZE];
+            for d in 1..POW_WINDOW_SIZE {
+                row[d] = bmul(row[d - 1], b);
+            }
+            // base^(16^(i + 1))
+            b = bmul(row[POW_WINDOW_SIZE - 1], b);
+            table.push(row);
+        }
+        BeltPowTable { table }
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Belt {
+        let mut res = 1;
+        for row in &self.table {
+            if exp == 0 {
+                break;
+            }
+            let d = (exp as usize) & (POW_WINDOW_SIZE - 1);
+            if d != 0 {
+                res = bmul(res, row[d]);
+            }
+            exp >>= POW_WINDOW_BITS;
+        }
+        Belt(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    extern crate std;
+    use core::hash::BuildHasher;
+    use std::collections::{hash_map::RandomState, HashSet};
+
+    #[test]
+    fn test_belts_ubig_roundtrip() {
+        let n = (UBig::from(1u8) << 250) - UBig::from(12345u32);
+        let belts = ubig_to_belts(&n, 8);
+        assert_eq!(belts.len(), 8);
+        assert!(belts.iter().all(|b| b.0 <= u32::MAX as u64));
+        assert_eq!(belts[0], Belt(u32::MAX as u64 - 12344));
+        assert_eq!(belts_to_ubig(&belts), n);
+
+        assert_eq!(ubig_to_belts(&UBig::from(0u8), 3), [Belt(0); 3]);
+        assert_eq!(
+            belts_to_ubig(&ubig_to_belts(&n, 2)),
+            n % (UBig::from(1u8) << 64)
+        );
+    }
+
+    #[test]
+    fn test_belt_debug_flags_out_of_field() {
+        assert_eq!(format!("{:?}", Belt(12345)), "Belt(12345)");
+        assert_eq!(
+            format!("{:?}", Belt(PRIME - 1)),
+            "Belt(18446744069414584320)"
+        );
+        assert_eq!(
+            format!("{:?}", Belt(PRIME + 1)),
+            "Belt(18446744069414584322 /* OUT OF FIELD */)"
+        );
+    }
+
+    #[test]
+    fn test_belt_pow_table() {
+        for base in [
+            Belt(0),
+            Belt(1),
+            Belt(7),
+            Belt(PRIME - 1),
+            Belt(0xdead_beef_1234),
+        ] {
+            let table = BeltPowTable::new(base);
+            for e in (0..100).chain([255, 256, 65537, 1 << 40, PRIME - 2]) {
+                assert_eq!(table.pow(e), base.pow(e as usize), "{base:?}^{e}");
+            }
+        }
+        // base^(p-1) == 1 for nonzero base
+        assert_eq!(BeltPowTable::new(Belt(7)).pow(PRIME - 1), Belt(1));
+    }
+
+    #[test]
+    fn test_batch_inv() {
+        let mut x = 0x1234_5678_9abc_def0u64;
+        let mut elems: Vec<Belt> = (0..300)
+            .map(|i| {
+                x = x
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                if i % 37 == 0 {
+                    Belt(0)
+                } else {
+                    Belt(x % PRIME)
+                }
+            })
+            .collect();
+        elems.push(Belt(1));
+        elems.push(Belt(PRIME - 1));
+        let expected: Vec<Belt> = elems
+            .iter()
+            .map(|e| if e.is_zero() { Belt(0) } else { e.inv() })
+            .collect();
+
+        Belt::batch_inv(&mut elems);
+        assert_eq!(elems, expected);
+
+        Belt::batch_inv(&mut []);
+        let mut zeros = [Belt(0); 3];
+        Belt::batch_inv(&mut zeros);
+        assert_eq!(zeros, [Belt(0); 3]);
+    }
+
+    #[test]
+    fn test_belt_sqrt() {
+        assert_eq!(Belt(0).sqrt(), Some(Belt(0)));
+        assert_eq!(Belt(1).sqrt(), Some(Belt(1)));
+        assert_eq!(Belt(4).sqrt(), Some(Belt(2)));
+        assert_eq!(Belt(PRIME - 4).sqrt().map(|r| r * r), Some(Belt(PRIME - 4)));
+        assert_eq!(Belt(7).sqrt(), None);
+
+        let mut x = 0xfeed_f00d_u64;
+        for _ in 0..200 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = Belt(x % PRIME);
+            let root = (a * a).sqrt().unwrap();
+            assert_eq!(root * root, a * a);
+            assert!(root == a || root == -a);
+            assert!(root.0 <= (-root).0);
+            // a nonzero element times a non-residue is a non-residue
+            if !a.is_zero() {
+                assert_eq!((a * a * Belt(7)).sqrt(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_belt_pow_u64() {
+        let base = Belt(7);
+        let table = BeltPowTable::new(base);
+        // exponents that do not fit a 32-bit usize
+        for e in [1u64 << 32, (1 << 40) + 3, PRIME - 2] {
+            assert_eq!(base.pow(e), table.pow(e), "{e}");
+            assert_eq!(base.pow(Belt(e)), table.pow(e), "{e}");
+        }
+        assert_eq!(base.pow(PRIME - 1), Belt(1));
+        assert_eq!(base.pow(PRIME - 2), base.inv());
+        assert_eq!(base.pow(5u64), base.pow(5usize));
+    }
+
+    #[test]
+    fn test_belt_hex() {
+        for b in [Belt(0), Belt(1), Belt(0xdead_beef), Belt(PRIME - 1)] {
+            assert_eq!(Belt::from_hex(&format!("{b:x}")).unwrap(), b);
+            assert_eq!(Belt::from_hex(&format!("{b:#x}")).unwrap(), b);
+        }
+        assert_eq!(format!("{:x}", Belt(PRIME - 1)), "ffffffff00000000");
+
+        assert!(matches!(
+            Belt::from_hex("ffffffff00000001"),
+            Err(FieldError::OutOfField(PRIME))
+        ));
+        assert!(matches!(
+            Belt::from_hex("ffffffffffffffff"),
+            Err(FieldError::OutOfField(u64::MAX))
+        ));
+        for bad in ["", "0x", "xyz", "1ffffffffffffffff", "-1"] {
+            assert!(
+                matches!(Belt::from_hex(bad), Err(FieldError::InvalidHex)),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_belt_deserialize_rejects_out_of_field() {
+        use alloc::string::ToString;
+
+        let json = serde_json::to_string(&Belt(PRIME - 1)).unwrap();
+        assert_eq!(json, (PRIME - 1).to_string());
+        assert_eq!(
+            serde_json::from_str::<Belt>(&json).unwrap(),
+            Belt(PRIME - 1)
+        );
+
+        let err = serde_json::from_str::<Belt>(&PRIME.to_string()).unwrap_err();
+        assert!(err.to_string().contains("out of field"), "{err}");
+        assert!(serde_json::from_str::<Belt>(&u64::MAX.to_string()).is_err());
+        assert!(serde_json::from_str::<[Belt; 2]>(&format!("[1, {PRIME}]")).is_err());
+    }
+
+    #[test]
+    fn test_belt_sum_product() {
+        let belts = [Belt(PRIME - 1), Belt(PRIME - 2), Belt(3), Belt(0xdead_beef)];
+        let sum = belts
+            .iter()
+            .fold(Belt::zero(), |acc, &b| badd(acc.0, b.0).into());
+        let product = belts
+            .iter()
+            .fold(Belt::one(), |acc, &b| bmul(acc.0, b.0).into());
+        assert_eq!(belts.iter().copied().sum::<Belt>(), sum);
+        assert_eq!(belts.iter().sum::<Belt>(), sum);
+        assert_eq!(belts.iter().copied().product::<Belt>(), product);
+        assert_eq!(belts.iter().product::<Belt>(), product);
+        // wraps past PRIME: -1 + -2 + 3 == 0
+        assert_eq!(belts[..3].iter().sum::<Belt>(), Belt(0));
+        // -1 * -2 * 3 == 6
+        assert_eq!(belts[..3].iter().product::<Belt>(), Belt(6));
+
+        assert_eq!(core::iter::empty::<Belt>().sum::<Belt>(), Belt::zero());
+        assert_eq!(core::iter::empty::<&Belt>().product::<Belt>(), Belt::one());
+    }
+
+    #[test]
+    fn test_reduction_flag() {
+        assert_eq!(Belt(1).add_with_reduction_flag(Belt(2)), (Belt(3), false));
+        assert_eq!(
+            Belt(PRIME - 1).add_with_reduction_flag(Belt(0)),
+            (Belt(PRIME - 1), false)
+        );
+        assert_eq!(
+            Belt(PRIME - 1).add_with_reduction_flag(Belt(1)),
+            (Belt(0), true)
+        );
+        assert_eq!(
+            Belt(PRIME - 1).add_with_reduction_flag(Belt(PRIME - 1)),
+            (Belt(PRIME - 2), true)
+        );
+
+        assert_eq!(Belt(3).sub_with_reduction_flag(Belt(3)), (Belt(0), false));
+        assert_eq!(
+            Belt(2).sub_with_reduction_flag(Belt(3)),
+            (Belt(PRIME - 1), true)
+        );
+    }
+
+    #[test]
+    fn test_belt_hash() {
+        let set: HashSet<Belt> = [Belt(42), Belt(42)].into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        let state = RandomState::new();
+        let hash = |b: Belt| state.hash_one(b);
+        assert_eq!(hash(Belt(PRIME + 5)), hash(Belt(5)));
+        assert_ne!(hash(Belt(6)), hash(Belt(5)));
+    }
+}
@@ -109,13 +109,28 @@ impl CheetahPoint {
         if *self == A_ID {
             return true;
         }
-        let scaled = ch_scal_big(&G_ORDER, self).unwrap();
-        scaled == A_ID
+        // Points off the curve can hit a division by zero along the way
+        matches!(ch_scal_big(&G_ORDER, self), Ok(scaled) if scaled == A_ID)
     }
 
     pub fn identity() -> Self {
         A_ID
     }
+
+    /// Point addition, see [`ch_add`]. The point at infinity is the identity.
+    pub fn add(&self, other: &CheetahPoint) -> Result<CheetahPoint, CheetahError> {
+        ch_add(self, other)
+    }
+
+    /// Point doubling, see [`ch_double`].
+    pub fn double(&self) -> Result<CheetahPoint, CheetahError> {
+        ch_double(*self)
+    }
+
+    /// Multiply the point by the scalar `k`, see [`ch_scal_big`].
+    pub fn scalar_mul(&self, k: &UBig) -> Result<CheetahPoint, CheetahError> {
+        ch_scal_big(k, self)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -346,3 +361,27 @@ pub fn trunc_g_order(a: &[u64]) -> UBig {
 
     result % &*G_ORDER
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let p = A_GEN;
+        let p2 = p.double().unwrap();
+        assert!(!p2.inf);
+        assert_eq!(p.add(&p).unwrap(), p2);
+        assert_eq!(p.scalar_mul(&UBig::from(2u8)).unwrap(), p2);
+        assert_eq!(p2.add(&p).unwrap(), p.scalar_mul(&UBig::from(3u8)).unwrap());
+        assert_eq!(p.scalar_mul(&G_ORDER).unwrap(), A_ID);
+
+        // the point at infinity is the identity
+        assert_eq!(p.add(&A_ID).unwrap(), p);
+        assert_eq!(A_ID.add(&p).unwrap(), p);
+        assert_eq!(A_ID.double().unwrap(), A_ID);
+        assert_eq!(A_ID.scalar_mul(&UBig::from(5u8)).unwrap(), A_ID);
+        assert_eq!(p.scalar_mul(&UBig::from(0u8)).unwrap(), A_ID);
+        assert_eq!(p.add(&ch_neg(&p)).unwrap(), A_ID);
+    }
+}
@@ -1,4 +1,5 @@
-use alloc::{fmt, string::String, vec, vec::Vec};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{fmt, format, string::String, string::ToString, vec, vec::Vec};
 use ibig::ops::DivRem;
 use ibig::UBig;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,12 @@ impl From<[u64; 5]> for Digest {
     }
 }
 
+impl From<&Digest> for Digest {
+    fn from(digest: &Digest) -> Self {
+        *digest
+    }
+}
+
 impl From<Digest> for Base58Belts<5> {
     fn from(digest: Digest) -> Self {
         Base58Belts(digest.0)
@@ -101,6 +108,114 @@ impl Digest {
     pub fn from_bytes(bytes: &[u8]) -> Self {
         Base58Belts::<5>::from_bytes(bytes).into()
     }
+
+    /// Lowercase hex of [`Digest::to_bytes`], without prefix.
+    pub fn to_hex(&self) -> String {
+        self.to_bytes().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// Parse the hex of [`Digest::to_bytes`], as returned by [`Digest::to_hex`].
+    ///
+    /// Pasted input is tolerated: an optional `0x`/`0X` prefix, whitespace and either case.
+    pub fn from_hex(s: &str) -> Result<Self, &'static str> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        let digits = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_digit(16).ok_or("invalid hex digit"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() != 80 {
+            return Err("digest hex must be 40 bytes");
+        }
+        let bytes: Vec<u8> = digits
+            .chunks_exact(2)
+            .map(|d| (d[0] * 16 + d[1]) as u8)
+            .collect();
+        let digest = Self::from_bytes(&bytes);
+        if digest.to_bytes()[..] != bytes[..] {
+            return Err("digest hex is out of field");
+        }
+        Ok(digest)
+    }
+
+    /// Raw belts as bytes, each belt little-endian, in belt order.
+    ///
+    /// Unlike [`Digest::to_bytes`], this does not go through the atom representation.
+    pub fn to_le_bytes(&self) -> [u8; 40] {
+        let mut bytes = [0u8; 40];
+        for (chunk, belt) in bytes.chunks_exact_mut(8).zip(&self.0) {
+            chunk.copy_from_slice(&belt.0.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Raw belts as bytes, each belt big-endian, in reverse belt order.
+    ///
+    /// This is the byte-reversal of [`Digest::to_le_bytes`].
+    pub fn to_be_bytes(&self) -> [u8; 40] {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Inverse of [`Digest::to_le_bytes`]. Returns `None` if any limb is not a field element.
+    pub fn from_le_bytes(bytes: &[u8; 40]) -> Option<Self> {
+        let mut belts = [Belt(0); 5];
+        for (belt, chunk) in belts.iter_mut().zip(bytes.chunks_exact(8)) {
+            let v = u64::from_le_bytes(chunk.try_into().unwrap());
+            if v >= PRIME {
+                return None;
+            }
+            *belt = Belt(v);
+        }
+        Some(Digest(belts))
+    }
+
+    /// Inverse of [`Digest::to_be_bytes`]. Returns `None` if any limb is not a field element.
+    pub fn from_be_bytes(bytes: &[u8; 40]) -> Option<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_le_bytes(&bytes)
+    }
+
+    /// Select `b` if `choice` is set, `a` otherwise, without branching on `choice`.
+    ///
+    /// The selection itself is limb-wise masking, but this is only constant-time if `choice` was
+    /// also computed without secret-dependent branches (e.g. by a constant-time comparison).
+    pub fn conditional_select(a: &Digest, b: &Digest, choice: bool) -> Digest {
+        // All ones if `choice`, all zeros otherwise. `black_box` keeps the optimizer from turning
+        // the mask back into a branch.
+        let mask = core::hint::black_box(choice as u64).wrapping_neg();
+        let mut out = [Belt(0); 5];
+        for (o, (x, y)) in out.iter_mut().zip(a.0.iter().zip(&b.0)) {
+            *o = Belt(x.0 ^ (mask & (x.0 ^ y.0)));
+        }
+        Digest(out)
+    }
+
+    /// Abbreviated base58 form for logs, keeping the first 6 and last 4 characters
+    /// (e.g. `3gF7od…xoEo`). Not meant to be parsed back.
+    pub fn short(&self) -> String {
+        self.abbreviate(6, 4)
+    }
+
+    /// Like [`Digest::short`], but keeping `len` characters on each side.
+    pub fn short_with(&self, len: usize) -> String {
+        self.abbreviate(len, len)
+    }
+
+    fn abbreviate(&self, prefix: usize, suffix: usize) -> String {
+        let s = self.to_string();
+        if prefix + suffix >= s.len() {
+            return s;
+        }
+        // Base58 is ASCII, so slicing by byte is fine.
+        format!("{}…{}", &s[..prefix], &s[s.len() - suffix..])
+    }
 }
 
 // Display and TryFrom implementations for Base58Belts<N>
@@ -138,6 +253,53 @@ impl TryFrom<&str> for Digest {
     }
 }
 
+/// Serde helper encoding a [`Digest`] as its base58 string, as shown by `Digest`'s `Display`.
+///
+/// Use with `#[serde(with = "rose_ztd::serde_digest")]`, so that every crate agrees on the JSON
+/// form of digests, rather than the array of belts of the derived impls.
+pub mod serde_digest {
+    use super::Digest;
+    use alloc::string::ToString;
+    use serde::de::{Error, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Digest, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Digest, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl Visitor<'_> for V {
+            type Value = Digest;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("base58 digest")
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let digest = Digest::try_from(s).map_err(E::custom)?;
+                // Reject out-of-field belts and other encodings that don't round trip.
+                if digest.to_string() != s {
+                    return Err(E::custom("non-canonical base58 digest"));
+                }
+                Ok(digest)
+            }
+        }
+
+        deserializer.deserialize_str(V)
+    }
+}
+
 pub fn hash_noun(leaves: &[Belt], dyck: &[Belt]) -> Digest {
     let mut combined = Vec::with_capacity(1 + leaves.len() + dyck.len());
     combined.push(Belt(leaves.len() as u64));
@@ -266,6 +428,28 @@ impl<T: Hashable> Hashable for Vec<T> {
     }
 }
 
+/// Hashed as the list of its elements in ascending order, same as the sorted `Vec`.
+///
+/// Note this is not the same as hashing the equivalent [`crate::ZSet`].
+impl<T: Hashable> Hashable for BTreeSet<T> {
+    fn hash(&self) -> Digest {
+        hash_list(self.iter().map(|v| v.hash()))
+    }
+}
+
+/// Hashed as the list of `(key, value)` pairs in ascending key order.
+///
+/// Note this is not the same as hashing the equivalent [`crate::ZMap`].
+impl<K: Hashable, V: Hashable> Hashable for BTreeMap<K, V> {
+    fn hash(&self) -> Digest {
+        hash_list(self.iter().map(|(k, v)| (k, v).hash()))
+    }
+}
+
+fn hash_list(items: impl DoubleEndedIterator<Item = Digest>) -> Digest {
+    items.rev().fold(0.hash(), |acc, item| (item, acc).hash())
+}
+
 impl Hashable for &str {
     fn hash(&self) -> Digest {
         self.bytes()
@@ -304,3 +488,158 @@ impl Hashable for Noun {
         hash_noun(&leaves, &dyck)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_digest_to_string_stability() {
+        let digest = Digest::from([1, 2, 3, 4, 5]);
+        let s = "11111112V9arU36gvtaofWmNowewoj9u7gbNA2qsJZEQ3WPky5mQ";
+        assert_eq!(digest.to_string(), s);
+        assert_eq!(Digest::try_from(s).unwrap(), digest);
+
+        let digest = Digest(hash_varlen(&mut vec![]).map(Belt));
+        assert_eq!(
+            digest.to_string(),
+            "3gF7odEPAQN4SArmbn9TFx9DpCqBDDVfoiLdKyj9UvUzRMiyWEbxoEo"
+        );
+    }
+
+    #[test]
+    fn test_digest_conditional_select() {
+        for i in 0..32u64 {
+            let a = Digest(hash_varlen(&mut vec![Belt(i)]).map(Belt));
+            let b = Digest(hash_varlen(&mut vec![Belt(i), Belt(1)]).map(Belt));
+            assert_eq!(Digest::conditional_select(&a, &b, false), a);
+            assert_eq!(Digest::conditional_select(&a, &b, true), b);
+            assert_eq!(Digest::conditional_select(&a, &a, true), a);
+        }
+    }
+
+    #[test]
+    fn test_digest_short() {
+        let digest = Digest(hash_varlen(&mut vec![]).map(Belt));
+        assert_eq!(digest.short(), "3gF7od…xoEo");
+        assert_eq!(digest.short(), digest.short());
+        assert_eq!(digest.short_with(3), "3gF…oEo");
+        assert_eq!(digest.short_with(30), digest.to_string());
+    }
+
+    #[test]
+    fn test_digest_le_be_bytes() {
+        let digest = Digest::from([1, 2, 3, 4, PRIME - 1]);
+
+        let le = digest.to_le_bytes();
+        assert_eq!(le[..8], 1u64.to_le_bytes());
+        assert_eq!(le[32..], (PRIME - 1).to_le_bytes());
+        assert_eq!(Digest::from_le_bytes(&le), Some(digest));
+
+        let be = digest.to_be_bytes();
+        assert_eq!(be[..8], (PRIME - 1).to_be_bytes());
+        assert_eq!(be[32..], 1u64.to_be_bytes());
+        assert_eq!(Digest::from_be_bytes(&be), Some(digest));
+    }
+
+    #[test]
+    fn test_digest_from_bytes_rejects_out_of_field() {
+        let mut le = Digest::from([1, 2, 3, 4, 5]).to_le_bytes();
+        le[8..16].copy_from_slice(&PRIME.to_le_bytes());
+        assert_eq!(Digest::from_le_bytes(&le), None);
+
+        le[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(Digest::from_le_bytes(&le), None);
+
+        let mut be = Digest::from([1, 2, 3, 4, 5]).to_be_bytes();
+        be[..8].copy_from_slice(&PRIME.to_be_bytes());
+        assert_eq!(Digest::from_be_bytes(&be), None);
+    }
+
+    #[test]
+    fn test_serde_stability() {
+        // Persisted wallet state relies on these, so changes must be deliberate.
+        assert_eq!(
+            serde_json::to_string(&Belt(PRIME - 1)).unwrap(),
+            "18446744069414584320"
+        );
+        let digest: Digest = "6psXufjYNRxffRx72w8FF9b5MYg8TEmWq2nEFkqYm51yfqsnkJu8XqX"
+            .try_into()
+            .unwrap();
+        let json = "[7409235290152290569,3024073258721519674,18209945911492618828,1387201985480977513,8448041072066161270]";
+        assert_eq!(serde_json::to_string(&digest).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Digest>(json).unwrap(), digest);
+    }
+
+    #[test]
+    fn test_serde_digest() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper {
+            #[serde(with = "crate::serde_digest")]
+            digest: Digest,
+        }
+
+        let digest = Digest::from([1, 2, 3, 4, PRIME - 1]);
+        let json = serde_json::to_string(&Wrapper { digest }).unwrap();
+        assert_eq!(json, format!("{{\"digest\":\"{digest}\"}}"));
+        assert_eq!(
+            serde_json::from_str::<Wrapper>(&json).unwrap(),
+            Wrapper { digest }
+        );
+
+        let out_of_field = Base58Belts([Belt(1), Belt(2), Belt(3), Belt(4), Belt(PRIME)]);
+        let json = format!("{{\"digest\":\"{out_of_field}\"}}");
+        assert!(serde_json::from_str::<Wrapper>(&json).is_err());
+        assert!(serde_json::from_str::<Wrapper>("{\"digest\":\"0OIl\"}").is_err());
+    }
+
+    #[test]
+    fn test_digest_hex() {
+        let digest = Digest::from([1, 2, 3, 4, PRIME - 1]);
+        let hex = digest.to_hex();
+        assert_eq!(hex.len(), 80);
+        assert_eq!(hex, hex.to_lowercase());
+        assert_eq!(Digest::from_hex(&hex), Ok(digest));
+        assert_eq!(Digest::from_hex(&format!("0x{hex}")), Ok(digest));
+        assert_eq!(
+            Digest::from_hex(&format!("0X{}", hex.to_uppercase())),
+            Ok(digest)
+        );
+        assert_eq!(
+            Digest::from_hex(&format!(" {} {} ", &hex[..40], &hex[40..])),
+            Ok(digest)
+        );
+
+        assert!(Digest::from_hex(&hex[..78]).is_err());
+        assert!(Digest::from_hex(&format!("{hex}00")).is_err());
+        assert!(Digest::from_hex(&format!("{}zz", &hex[..78])).is_err());
+        assert!(Digest::from_hex(&"ff".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn test_btree_hash_matches_sorted_vec() {
+        let digests = [5u64, 1, 3, 2].map(|v| v.hash());
+        let set: BTreeSet<Digest> = digests.iter().copied().collect();
+        let mut sorted = digests.to_vec();
+        sorted.sort();
+        assert_eq!(set.hash(), sorted.hash());
+        assert_eq!(
+            BTreeSet::<Digest>::new().hash(),
+            Vec::<Digest>::new().hash()
+        );
+
+        let map: BTreeMap<u64, Digest> = [(2, digests[0]), (1, digests[1])].into();
+        assert_eq!(map.hash(), vec![(1, digests[1]), (2, digests[0])].hash());
+    }
+
+    #[test]
+    fn test_hashable_stability() {
+        assert_eq!(
+            0u64.hash().to_string(),
+            "3Ssr4tiWsbX5CE3AG6p5qPHP51fiyvtt1XEEHmSbGgDjp3qjUew6DFB"
+        );
+        assert_eq!(true.hash(), 0u64.hash());
+        assert_eq!(Noun::Atom(UBig::from(1u64)).hash(), 1u64.hash());
+    }
+}
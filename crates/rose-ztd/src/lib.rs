@@ -5,12 +5,14 @@ extern crate alloc;
 pub mod crypto;
 pub mod tip5;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 mod belt;
 mod hash;
-mod noun;
+pub mod noun;
 mod zmap;
 mod zset;
-pub use belt::Belt;
+pub use belt::{belts_to_ubig, ubig_to_belts, Belt, BeltPowTable, FieldError, PRIME};
 pub use hash::*;
 pub use noun::*;
 pub use zmap::*;
@@ -52,6 +52,39 @@ pub enum Noun {
 }
 
 impl Noun {
+    /// Number of nested cells on the deepest path, an atom having depth 0.
+    ///
+    /// Recursive functions on nouns (hashing, encoding, dropping) use stack proportional to this,
+    /// so check it on untrusted nouns. Computed without recursion.
+    pub fn depth(&self) -> usize {
+        let mut max = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((noun, depth)) = stack.pop() {
+            match noun {
+                Noun::Atom(_) => max = max.max(depth),
+                Noun::Cell(head, tail) => {
+                    stack.push((head, depth + 1));
+                    stack.push((tail, depth + 1));
+                }
+            }
+        }
+        max
+    }
+
+    /// Number of cells in the noun, counting shared subnouns once per occurrence.
+    pub fn cell_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(noun) = stack.pop() {
+            if let Noun::Cell(head, tail) = noun {
+                count += 1;
+                stack.push(head);
+                stack.push(tail);
+            }
+        }
+        count
+    }
+
     #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         fn autocons(cell: &Noun) -> String {
@@ -148,14 +181,35 @@ pub trait NounDecode: Sized {
     fn from_noun(noun: &Noun) -> Option<Self>;
 }
 
-fn atom(value: u64) -> Noun {
+pub fn atom(value: u64) -> Noun {
     Noun::Atom(UBig::from(value))
 }
 
-fn cons(left: Noun, right: Noun) -> Noun {
+pub fn cons(left: Noun, right: Noun) -> Noun {
     Noun::Cell(Box::new(left), Box::new(right))
 }
 
+/// Hoon list `[a b ... z ~]`, as decoded by the `Vec` [`NounDecode`] impl.
+pub fn list(items: impl IntoIterator<Item = Noun>) -> Noun {
+    let items: Vec<Noun> = items.into_iter().collect();
+    items
+        .into_iter()
+        .rev()
+        .fold(atom(0), |acc, item| cons(item, acc))
+}
+
+/// Right-nested tuple `[a b ... z]` without a terminating `~`, the layout of Rust tuples and of
+/// derived [`NounEncode`] impls. An empty tuple is `~`.
+pub fn tuple(items: &[Noun]) -> Noun {
+    match items.split_last() {
+        None => atom(0),
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(last.clone(), |acc, item| cons(item.clone(), acc)),
+    }
+}
+
 impl<T: NounEncode + ?Sized> NounEncode for &T {
     fn to_noun(&self) -> Noun {
         (**self).to_noun()
@@ -372,6 +426,8 @@ impl_nounable_for_tuple!(
     K => k
 );
 
+/// Fixed-size arrays (e.g. `[Belt; 5]` for digests, `[Belt; 6]` for `F6lt`) encode as a
+/// right-nested tuple `[a b ... z]` without a terminating `~`, same as the equivalent tuple.
 impl<T: NounEncode, const N: usize> NounEncode for [T; N] {
     fn to_noun(&self) -> Noun {
         match self.split_last() {
@@ -391,12 +447,13 @@ impl<T: NounDecode, const N: usize> NounDecode for [T; N] {
     fn from_noun(mut noun: &Noun) -> Option<Self> {
         let mut ret: [Option<T>; N] = [(); N].map(|_| None);
         for (i, item) in ret.iter_mut().enumerate() {
+            // The last element takes the whole remaining noun, as with tuples.
             let decode = match noun {
+                v if i == N - 1 => v,
                 Noun::Cell(a, b) => {
                     noun = b;
                     a
                 }
-                v if i == N - 1 => v,
                 _ => return None,
             };
             *item = Some(T::from_noun(decode)?);
@@ -568,14 +625,71 @@ pub fn jam(noun: Noun) -> Vec<u8> {
     buffer.into_vec()
 }
 
+/// Reason [`cue_checked`] failed to decode a jammed noun
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NounError {
+    /// The input ended in the middle of a noun
+    UnexpectedEof(usize),
+    /// A backreference was wider than 64 bits
+    BackrefTooLarge(usize),
+    /// A backreference pointed to a position where no noun starts
+    InvalidBackref(u64),
+    /// The noun is deeper than the limit passed to [`cue_with_max_depth`]
+    TooDeep(usize),
+}
+
+impl fmt::Display for NounError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NounError::UnexpectedEof(cursor) => {
+                write!(f, "Unexpected end of jam at bit {cursor}")
+            }
+            NounError::BackrefTooLarge(size) => {
+                write!(f, "Backreference too large ({size} bits)")
+            }
+            NounError::InvalidBackref(backref) => {
+                write!(f, "Invalid backreference to bit {backref}")
+            }
+            NounError::TooDeep(max_depth) => {
+                write!(f, "Noun is deeper than {max_depth} cells")
+            }
+        }
+    }
+}
+
 pub fn cue(bytes: &[u8]) -> Option<Noun> {
-    cue_bitslice(BitSlice::from_slice(bytes))
+    cue_checked(bytes).ok()
 }
 
 pub fn cue_bitslice(buffer: &BitSlice<u8, Lsb0>) -> Option<Noun> {
+    cue_bitslice_checked(buffer).ok()
+}
+
+/// Same as [`cue`], but reports why decoding failed.
+pub fn cue_checked(bytes: &[u8]) -> Result<Noun, NounError> {
+    cue_bitslice_checked(BitSlice::from_slice(bytes))
+}
+
+/// Same as [`cue_bitslice`], but reports why decoding failed.
+pub fn cue_bitslice_checked(buffer: &BitSlice<u8, Lsb0>) -> Result<Noun, NounError> {
+    cue_bitslice_with_max_depth(buffer, usize::MAX)
+}
+
+/// Same as [`cue_checked`], but fails with [`NounError::TooDeep`] as soon as the noun gets deeper
+/// than `max_depth` (see [`Noun::depth`]). Use this on untrusted input.
+pub fn cue_with_max_depth(bytes: &[u8], max_depth: usize) -> Result<Noun, NounError> {
+    cue_bitslice_with_max_depth(BitSlice::from_slice(bytes), max_depth)
+}
+
+/// Same as [`cue_with_max_depth`], on a bitslice.
+pub fn cue_bitslice_with_max_depth(
+    buffer: &BitSlice<u8, Lsb0>,
+    max_depth: usize,
+) -> Result<Noun, NounError> {
     #[derive(Copy, Clone)]
     enum CueStackEntry {
-        DestinationPointer(*mut Noun),
+        /// Where to write the next noun, and the number of cells above it
+        DestinationPointer(*mut Noun, usize),
         BackRef(u64, *mut Noun),
     }
 
@@ -603,54 +717,58 @@ pub fn cue_bitslice(buffer: &BitSlice<u8, Lsb0>) -> Option<Noun> {
         }
     }
 
-    fn get_size(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Option<usize> {
+    fn get_size(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Result<usize, NounError> {
         let buff_at_cursor = rest_bits(*cursor, buffer);
-        let bitsize = buff_at_cursor.first_one()?;
+        let bitsize = buff_at_cursor
+            .first_one()
+            .ok_or(NounError::UnexpectedEof(*cursor))?;
         if bitsize == 0 {
             *cursor += 1;
-            Some(0)
+            Ok(0)
         } else {
             let mut size = [0u8; 8];
             *cursor += bitsize + 1;
             let size_bits = next_up_to_n_bits(cursor, buffer, bitsize - 1);
             BitSlice::from_slice_mut(&mut size)[0..bitsize - 1].copy_from_bitslice(size_bits);
-            Some((u64::from_le_bytes(size) as usize) + (1 << (bitsize - 1)))
+            Ok((u64::from_le_bytes(size) as usize) + (1 << (bitsize - 1)))
         }
     }
 
-    fn rub_backref(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Option<u64> {
+    fn rub_backref(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Result<u64, NounError> {
         // TODO: What's size here usually?
         let size = get_size(cursor, buffer)?;
         if size == 0 {
-            Some(0)
+            Ok(0)
         } else if size <= 64 {
             // TODO: Size <= 64, so we can fit the backref in a direct atom?
+            let bits = buffer
+                .get(*cursor..*cursor + size)
+                .ok_or(NounError::UnexpectedEof(*cursor))?;
             let mut backref = [0u8; 8];
-            BitSlice::from_slice_mut(&mut backref)[0..size]
-                .copy_from_bitslice(&buffer[*cursor..*cursor + size]);
+            BitSlice::from_slice_mut(&mut backref)[0..size].copy_from_bitslice(bits);
             *cursor += size;
-            Some(u64::from_le_bytes(backref))
+            Ok(u64::from_le_bytes(backref))
         } else {
-            None
+            Err(NounError::BackrefTooLarge(size))
         }
     }
 
-    fn rub_atom(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Option<UBig> {
+    fn rub_atom(cursor: &mut usize, buffer: &BitSlice<u8, Lsb0>) -> Result<UBig, NounError> {
         let size = get_size(cursor, buffer)?;
         let bits = next_up_to_n_bits(cursor, buffer, size);
         if size == 0 {
-            Some(UBig::from(0u64))
+            Ok(UBig::from(0u64))
         } else if size < 64 {
             // Fits in a direct atom
             let mut direct_raw = [0u8; 8];
             BitSlice::from_slice_mut(&mut direct_raw)[0..bits.len()].copy_from_bitslice(bits);
-            Some(UBig::from(u64::from_le_bytes(direct_raw)))
+            Ok(UBig::from(u64::from_le_bytes(direct_raw)))
         } else {
             // Need an indirect atom
             let wordsize = (size + 63) >> 6;
             let mut bytes = vec![0u8; wordsize * 8];
             BitSlice::from_slice_mut(&mut bytes).copy_from_bitslice(bits);
-            Some(UBig::from_le_bytes(&bytes))
+            Ok(UBig::from_le_bytes(&bytes))
         }
     }
 
@@ -670,21 +788,35 @@ pub fn cue_bitslice(buffer: &BitSlice<u8, Lsb0>) -> Option<Noun> {
 
     let mut cue_stack = vec![];
 
-    cue_stack.push(CueStackEntry::DestinationPointer(&mut result as *mut Noun));
+    cue_stack.push(CueStackEntry::DestinationPointer(
+        &mut result as *mut Noun,
+        0,
+    ));
 
     while let Some(stack_entry) = cue_stack.pop() {
         unsafe {
             // Capture the destination pointer and pop it off the stack
             match stack_entry {
-                CueStackEntry::DestinationPointer(dest_ptr) => {
+                CueStackEntry::DestinationPointer(dest_ptr, depth) => {
                     // 1 bit
                     if next_bit(&mut cursor, buffer) {
                         // 11 tag: backref
                         if next_bit(&mut cursor, buffer) {
                             let backref = rub_backref(&mut cursor, buffer)?;
-                            *dest_ptr = (**backref_map.get(&backref)?).clone();
+                            let target = &**backref_map
+                                .get(&backref)
+                                .ok_or(NounError::InvalidBackref(backref))?;
+                            if max_depth != usize::MAX
+                                && depth.saturating_add(target.depth()) > max_depth
+                            {
+                                return Err(NounError::TooDeep(max_depth));
+                            }
+                            *dest_ptr = target.clone();
                         } else {
                             // 10 tag: cell
+                            if depth >= max_depth {
+                                return Err(NounError::TooDeep(max_depth));
+                            }
                             let mut head = Box::new(atom(0));
                             let head_ptr = (&mut *head) as *mut _;
                             let mut tail = Box::new(atom(0));
@@ -693,8 +825,8 @@ pub fn cue_bitslice(buffer: &BitSlice<u8, Lsb0>) -> Option<Noun> {
                             let backref = (cursor - 2) as u64;
                             backref_map.insert(backref, dest_ptr);
                             cue_stack.push(CueStackEntry::BackRef(cursor as u64 - 2, dest_ptr));
-                            cue_stack.push(CueStackEntry::DestinationPointer(tail_ptr));
-                            cue_stack.push(CueStackEntry::DestinationPointer(head_ptr));
+                            cue_stack.push(CueStackEntry::DestinationPointer(tail_ptr, depth + 1));
+                            cue_stack.push(CueStackEntry::DestinationPointer(head_ptr, depth + 1));
                         }
                     } else {
                         // 0 tag: atom
@@ -710,5 +842,106 @@ pub fn cue_bitslice(buffer: &BitSlice<u8, Lsb0>) -> Option<Noun> {
         }
     }
 
-    Some(result)
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn belts<const N: usize>() -> [Belt; N] {
+        core::array::from_fn(|i| Belt(i as u64 + 1))
+    }
+
+    #[test]
+    fn test_cue_checked_errors() {
+        let noun = (1u64, (2u64, 1u64)).to_noun();
+        assert_eq!(cue_checked(&jam(noun.clone())), Ok(noun));
+
+        // Cell tag with nothing after it
+        let err = cue_checked(&[0b01]).unwrap_err();
+        assert_eq!(err, NounError::UnexpectedEof(3));
+        assert_eq!(format!("{err}"), "Unexpected end of jam at bit 3");
+
+        // Backreference to bit 1, where no noun starts: tag 11, size 1, bit 1
+        let err = cue_checked(&[0b11_1011]).unwrap_err();
+        assert_eq!(err, NounError::InvalidBackref(1));
+        assert_eq!(format!("{err}"), "Invalid backreference to bit 1");
+        assert_eq!(cue(&[0b11_1011]), None);
+    }
+
+    #[test]
+    fn test_noun_depth() {
+        assert_eq!(atom(7).depth(), 0);
+        assert_eq!(atom(7).cell_count(), 0);
+        let noun = (1u64, (2u64, 3u64), 4u64).to_noun();
+        assert_eq!(noun.depth(), 3);
+        assert_eq!(noun.cell_count(), 3);
+
+        // a list nests one cell per item
+        let deep = list((0..2_000).map(atom));
+        assert_eq!(deep.depth(), 2_000);
+        assert_eq!(deep.cell_count(), 2_000);
+
+        let jammed = jam(deep.clone());
+        assert_eq!(cue_with_max_depth(&jammed, 2_000), Ok(deep.clone()));
+        let err = cue_with_max_depth(&jammed, 1_999).unwrap_err();
+        assert_eq!(err, NounError::TooDeep(1_999));
+        assert_eq!(format!("{err}"), "Noun is deeper than 1999 cells");
+
+        // depth reached through a backreference is counted too
+        let repeated = (deep.clone(), deep.clone()).to_noun();
+        assert_eq!(repeated.depth(), 2_001);
+        let jammed = jam(repeated.clone());
+        assert!(jammed.len() < 2 * jam(deep).len());
+        assert_eq!(cue_with_max_depth(&jammed, 2_001), Ok(repeated));
+        assert_eq!(
+            cue_with_max_depth(&jammed, 2_000),
+            Err(NounError::TooDeep(2_000))
+        );
+    }
+
+    #[test]
+    fn test_list_and_tuple() {
+        let (a, b, c) = (atom(1), cons(atom(2), atom(3)), atom(4));
+
+        let l = list([a.clone(), b.clone()]);
+        assert_eq!(l, cons(a.clone(), cons(b.clone(), atom(0))));
+        assert_eq!(Vec::<Noun>::from_noun(&l), Some(vec![a.clone(), b.clone()]));
+        assert_eq!(list([]), atom(0));
+
+        let t = tuple(&[a.clone(), b.clone(), c.clone()]);
+        assert_eq!(t, (&a, &b, &c).to_noun());
+        assert_eq!(tuple(core::slice::from_ref(&a)), a);
+        assert_eq!(tuple(&[]), atom(0));
+        assert_eq!(
+            <(u64, (u64, u64), u64)>::from_noun(&t),
+            Some((1, (2, 3), 4))
+        );
+    }
+
+    #[test]
+    fn test_belt_array_roundtrip() {
+        let five: [Belt; 5] = belts();
+        assert_eq!(<[Belt; 5]>::from_noun(&five.to_noun()), Some(five));
+        let six: [Belt; 6] = belts();
+        assert_eq!(<[Belt; 6]>::from_noun(&six.to_noun()), Some(six));
+        let eight: [Belt; 8] = belts();
+        assert_eq!(<[Belt; 8]>::from_noun(&eight.to_noun()), Some(eight));
+
+        // Wrong arity is rejected
+        assert_eq!(<[Belt; 6]>::from_noun(&five.to_noun()), None);
+        assert_eq!(<[Belt; 5]>::from_noun(&six.to_noun()), None);
+    }
+
+    #[test]
+    fn test_digest_noun_matches_tuple() {
+        let [a, b, c, d, e] = belts();
+        let digest = Digest([a, b, c, d, e]);
+        assert_eq!(jam(digest.to_noun()), jam((a, b, c, d, e).to_noun()));
+
+        let f6: [Belt; 6] = belts();
+        let [a, b, c, d, e, f] = f6;
+        assert_eq!(jam(F6lt(f6).0.to_noun()), jam((a, b, c, d, e, f).to_noun()));
+    }
 }
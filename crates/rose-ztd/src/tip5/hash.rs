@@ -123,3 +123,66 @@ pub fn hash_fixed(input_vec: &mut Vec<Belt>) -> [u64; 5] {
     //  calc digest
     tip5_calc_digest(&sponge)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::belt::PRIME;
+    use alloc::vec;
+
+    // Tip5 output is consensus-critical: these vectors must never change.
+
+    #[test]
+    fn test_hash_varlen_empty() {
+        assert_eq!(
+            hash_varlen(&mut vec![]),
+            [
+                11048995573592393898,
+                6655187932135147625,
+                8573492257662932655,
+                4379820112787053727,
+                3881663824627898703,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_varlen_vectors() {
+        // exactly one rate-sized chunk, plus padding chunk
+        assert_eq!(
+            hash_varlen(&mut (1..=10).map(Belt).collect()),
+            [
+                12735212915971076565,
+                7789085617489624939,
+                4619502071071326909,
+                6036885477939560831,
+                2966144383908523453,
+            ]
+        );
+        // spans two chunks, with max field elements
+        assert_eq!(
+            hash_varlen(&mut vec![Belt(PRIME - 1); 13]),
+            [
+                8188810938269586563,
+                13949062549066468677,
+                15633628986240765684,
+                2641530549824511307,
+                13930947068068449333,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_fixed_vector() {
+        assert_eq!(
+            hash_fixed(&mut (0..10).map(Belt).collect()),
+            [
+                15056352079679291843,
+                7747109957227225216,
+                5953953536499948464,
+                9253444947603856218,
+                6415804733172141563,
+            ]
+        );
+    }
+}